@@ -0,0 +1,75 @@
+use benchmarks::{
+    GoldenFunction, for_each_runtime, golden_functions, setup_golden, time_sync_calls,
+};
+use criterion::async_executor::FuturesExecutor;
+use criterion::{Criterion, criterion_group, criterion_main};
+use engine::{EngineOptions, Runtime, Value};
+use std::hint::black_box;
+use std::time::Instant;
+
+/// Benchmark `function` through `Func::call` on a sync engine and through
+/// `call_async` on an async engine of runtime `R`, side by side in one group
+///
+/// The gap between both is the fiber and stack switching overhead per call.
+fn benchmark_sync_vs_async<R: Runtime>(c: &mut Criterion, function: &GoldenFunction) {
+    let params: Vec<R::Val> = function.params.iter().cloned().map(R::Val::from).collect();
+    let params = params.as_slice();
+
+    let (sync_engine, sync_pre) = setup_golden::<R>(function, &EngineOptions::default());
+    let async_options = EngineOptions {
+        async_support: true,
+        ..Default::default()
+    };
+    let (async_engine, async_pre) = setup_golden::<R>(function, &async_options);
+
+    let mut group = c.benchmark_group(format!("call_{}_{}", function.label(), R::NAME));
+
+    group.bench_function("sync", |b| {
+        b.iter_custom(|iters| {
+            time_sync_calls::<R>(
+                R::new_store(&sync_engine),
+                &sync_pre,
+                function,
+                params,
+                iters,
+            )
+        })
+    });
+
+    group.bench_function("async", |b| {
+        b.to_async(FuturesExecutor).iter_custom(|iters| async move {
+            let mut store = R::new_store(&async_engine);
+            let instance = R::instantiate_async(&async_pre, &mut store)
+                .await
+                .expect("Instantiation failed");
+            let func = R::find_func(&instance, &mut store, function.name).expect("find func");
+            let mut results = vec![R::Val::from(Value::Bool(false)); R::results_len(&func, &store)];
+
+            let start = Instant::now();
+
+            for _ in 0..iters {
+                R::call_async(&func, &mut store, params, &mut results)
+                    .await
+                    .expect("Call failed");
+                black_box(
+                    R::post_return_async(&func, &mut store)
+                        .await
+                        .expect("unexpected error"),
+                );
+            }
+
+            start.elapsed()
+        })
+    });
+
+    group.finish();
+}
+
+fn benchmark_sync_vs_async_all(c: &mut Criterion) {
+    for function in golden_functions() {
+        for_each_runtime!(R => benchmark_sync_vs_async::<R>(c, &function));
+    }
+}
+
+criterion_group!(benches, benchmark_sync_vs_async_all);
+criterion_main!(benches);
@@ -0,0 +1,147 @@
+use benchmarks::{
+    GoldenFunction, for_each_runtime, get_golden_wasm_path, get_report_dir, golden_functions,
+};
+use criterion::{Criterion, criterion_group, criterion_main};
+use engine::{EngineOptions, Runtime, Value};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// Number of pipeline runs the reported phase breakdown is the median of
+const BREAKDOWN_RUNS: usize = 5;
+
+/// Time spent in each phase of one cold start
+#[derive(Debug, Clone, Copy, Default)]
+struct Phases {
+    /// `load_component`: read the file and compile it
+    load: Duration,
+    /// `Linker::new` and adding WASI preview2
+    link: Duration,
+    instantiate_pre: Duration,
+    instantiate: Duration,
+    /// Finding the function, the first `call` and its `post_return`
+    first_call: Duration,
+}
+
+impl Phases {
+    fn total(&self) -> Duration {
+        self.load + self.link + self.instantiate_pre + self.instantiate + self.first_call
+    }
+}
+
+/// Run the whole pipeline once from the component file to the first call
+///
+/// The engine is shared, like a server keeps it across requests.
+fn cold_start<R: Runtime>(
+    engine: &R::Engine,
+    options: &EngineOptions,
+    function: &GoldenFunction,
+    params: &[R::Val],
+) -> Phases {
+    let mut phases = Phases::default();
+
+    let start = Instant::now();
+    let component = R::load_component(engine, &get_golden_wasm_path(function.wasm_file))
+        .expect("Load component failed");
+    phases.load = start.elapsed();
+
+    let start = Instant::now();
+    let linker = R::new_linker(engine, options).expect("Link WASI failed");
+    phases.link = start.elapsed();
+
+    let start = Instant::now();
+    let pre_instance = R::link_instantiate_pre(&linker, &component).expect("instantiate-pre");
+    phases.instantiate_pre = start.elapsed();
+
+    let start = Instant::now();
+    let mut store = R::new_store(engine);
+    let instance = R::instantiate(&pre_instance, &mut store).expect("Instantiation failed");
+    phases.instantiate = start.elapsed();
+
+    let start = Instant::now();
+    let func = R::find_func(&instance, &mut store, function.name).expect("find func");
+    let mut results = vec![R::Val::from(Value::Bool(false)); R::results_len(&func, &store)];
+    R::call(&func, &mut store, params, &mut results).expect("Call failed");
+    R::post_return(&func, &mut store).expect("unexpected error");
+    phases.first_call = start.elapsed();
+
+    black_box(results);
+    phases
+}
+
+/// Run the pipeline `BREAKDOWN_RUNS` times, print the median of every phase
+/// and save it as `target/cold_start/<function>_<version>.json` for
+/// collect_report.py
+fn report_breakdown<R: Runtime>(
+    engine: &R::Engine,
+    options: &EngineOptions,
+    function: &GoldenFunction,
+    params: &[R::Val],
+) {
+    let runs: Vec<Phases> = (0..BREAKDOWN_RUNS)
+        .map(|_| cold_start::<R>(engine, options, function, params))
+        .collect();
+    let median = |phase: fn(&Phases) -> Duration| {
+        let mut durations: Vec<Duration> = runs.iter().map(phase).collect();
+        durations.sort();
+        durations[durations.len() / 2].as_secs_f64() * 1e6
+    };
+
+    let name = format!("{}_{}", function.label(), R::NAME);
+    let phases = [
+        ("load", median(|p| p.load)),
+        ("link", median(|p| p.link)),
+        ("instantiate_pre", median(|p| p.instantiate_pre)),
+        ("instantiate", median(|p| p.instantiate)),
+        ("first_call", median(|p| p.first_call)),
+        ("total", median(Phases::total)),
+    ];
+
+    let breakdown: Vec<String> = phases
+        .iter()
+        .map(|(phase, us)| format!("{phase} {us:.1}µs"))
+        .collect();
+    println!("cold_start_{name}: {}", breakdown.join(", "));
+
+    let dir = get_report_dir("cold_start");
+    std::fs::create_dir_all(&dir).expect("create cold_start report dir");
+    let fields: Vec<String> = phases
+        .iter()
+        .map(|(phase, us)| format!(r#""{phase}": {us}"#))
+        .collect();
+    let report = format!(
+        r#"{{"function": "{}", "version": "{}", "engine": "{}", "phases_us": {{{}}}}}"#,
+        function.label(),
+        R::NAME,
+        R::version(),
+        fields.join(", ")
+    );
+    std::fs::write(dir.join(format!("{name}.json")), report).expect("write cold start breakdown");
+}
+
+/// Benchmark the cold start of `function` with runtime `R`
+fn benchmark_cold_start<R: Runtime>(c: &mut Criterion, function: &GoldenFunction) {
+    let options = EngineOptions::default();
+    let engine = R::new_engine(&options).expect("Setup engine failed");
+    let params: Vec<R::Val> = function.params.iter().cloned().map(R::Val::from).collect();
+
+    report_breakdown::<R>(&engine, &options, function, &params);
+
+    c.bench_function(
+        &format!("cold_start_{}_{}", function.label(), R::NAME),
+        |b| b.iter(|| cold_start::<R>(&engine, &options, function, &params)),
+    );
+}
+
+fn benchmark_cold_start_all(c: &mut Criterion) {
+    for function in golden_functions() {
+        for_each_runtime!(R => benchmark_cold_start::<R>(c, &function));
+    }
+}
+
+criterion_group!(
+    name = benches;
+    // Every iteration compiles the component from scratch
+    config = Criterion::default().sample_size(10);
+    targets = benchmark_cold_start_all
+);
+criterion_main!(benches);
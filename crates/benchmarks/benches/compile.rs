@@ -0,0 +1,101 @@
+use benchmarks::{GOLDEN_COMPONENTS, for_each_runtime, get_golden_wasm_path};
+use criterion::{Criterion, criterion_group, criterion_main};
+use engine::{EngineOptions, Runtime};
+use std::hint::black_box;
+use testdata::wasmi;
+
+/// Suffix of the benchmark name for the parallel compilation setting
+fn compilation_label(parallel_compilation: bool) -> &'static str {
+    if parallel_compilation {
+        "parallel"
+    } else {
+        "serial"
+    }
+}
+
+/// Benchmark Cranelift compilation of a golden component with runtime `R`
+fn benchmark_compile_component<R: Runtime>(
+    c: &mut Criterion,
+    wasm_file: &str,
+    parallel_compilation: bool,
+) {
+    let options = EngineOptions {
+        parallel_compilation,
+        ..Default::default()
+    };
+    let engine = R::new_engine(&options).expect("Setup engine failed");
+
+    // Read the file up front so only compilation is timed
+    let wasm_bytes = std::fs::read(get_golden_wasm_path(wasm_file)).expect("read component");
+
+    let name = format!(
+        "compile_{}_{}_{}",
+        wasm_file.replace(".wasm", ""),
+        R::NAME,
+        compilation_label(parallel_compilation)
+    );
+    c.bench_function(&name, |b| {
+        b.iter(|| {
+            black_box(R::compile_component(&engine, &wasm_bytes).expect("Compilation failed"))
+        })
+    });
+}
+
+/// Benchmark Cranelift compilation of a wasmi-benchmarks core module with
+/// runtime `R`
+fn benchmark_compile_module<R: Runtime>(
+    c: &mut Criterion,
+    name: &str,
+    wasm_bytes: &[u8],
+    parallel_compilation: bool,
+) {
+    let options = EngineOptions {
+        parallel_compilation,
+        ..Default::default()
+    };
+    let engine = R::new_engine(&options).expect("Setup engine failed");
+
+    let name = format!(
+        "compile_wasmi_{name}_{}_{}",
+        R::NAME,
+        compilation_label(parallel_compilation)
+    );
+    c.bench_function(&name, |b| {
+        b.iter(|| black_box(R::load_module(&engine, wasm_bytes).expect("Compilation failed")))
+    });
+}
+
+/// Benchmark compilation of every golden component
+fn benchmark_compile_golden(c: &mut Criterion) {
+    for wasm_file in GOLDEN_COMPONENTS {
+        for parallel_compilation in [true, false] {
+            for_each_runtime!(R => benchmark_compile_component::<R>(
+                c,
+                wasm_file,
+                parallel_compilation,
+            ));
+        }
+    }
+}
+
+/// Benchmark compilation of every wasmi-benchmarks module
+fn benchmark_compile_wasmi(c: &mut Criterion) {
+    for (name, wasm_bytes) in wasmi::ALL {
+        for parallel_compilation in [true, false] {
+            for_each_runtime!(R => benchmark_compile_module::<R>(
+                c,
+                name,
+                wasm_bytes,
+                parallel_compilation,
+            ));
+        }
+    }
+}
+
+criterion_group!(
+    name = benches;
+    // Compiling ffmpeg or spidermonkey serially takes seconds
+    config = Criterion::default().sample_size(10);
+    targets = benchmark_compile_golden, benchmark_compile_wasmi
+);
+criterion_main!(benches);
@@ -0,0 +1,54 @@
+use benchmarks::{coremark_score, for_each_runtime, get_report_dir, setup_coremark};
+use criterion::{Criterion, criterion_group, criterion_main};
+use engine::Runtime;
+use std::hint::black_box;
+
+/// Number of CoreMark runs the reported score is the median of
+const SCORE_RUNS: usize = 5;
+
+/// Run CoreMark `SCORE_RUNS` times, print the median score and save it as
+/// `target/coremark/<version>.json` for collect_report.py
+fn report_coremark_score<R: Runtime>(engine: &R::Engine, pre_instance: &R::CoreInstancePre) {
+    let mut scores: Vec<f64> = (0..SCORE_RUNS)
+        .map(|_| coremark_score::<R>(engine, pre_instance).expect("CoreMark failed"))
+        .collect();
+    scores.sort_by(f64::total_cmp);
+    let median = scores[scores.len() / 2];
+
+    println!(
+        "coremark_{}: {median:.2} iterations/s (runs: {scores:?})",
+        R::NAME
+    );
+
+    let dir = get_report_dir("coremark");
+    std::fs::create_dir_all(&dir).expect("create coremark report dir");
+    let report = format!(
+        r#"{{"version": "{}", "engine": "{}", "score": {median}, "runs": {scores:?}}}"#,
+        R::NAME,
+        R::version()
+    );
+    std::fs::write(dir.join(format!("{}.json", R::NAME)), report).expect("write coremark score");
+}
+
+/// Report the CoreMark score of runtime `R` and time one CoreMark run
+fn benchmark_coremark<R: Runtime>(c: &mut Criterion) {
+    let (engine, pre_instance) = setup_coremark::<R>().expect("Setup coremark failed");
+
+    report_coremark_score::<R>(&engine, &pre_instance);
+
+    c.bench_function(&format!("coremark_{}", R::NAME), |b| {
+        b.iter(|| black_box(coremark_score::<R>(&engine, &pre_instance).expect("CoreMark failed")))
+    });
+}
+
+fn benchmark_coremark_all(c: &mut Criterion) {
+    for_each_runtime!(R => benchmark_coremark::<R>(c));
+}
+
+criterion_group!(
+    name = benches;
+    // A single CoreMark run takes seconds
+    config = Criterion::default().sample_size(10);
+    targets = benchmark_coremark_all
+);
+criterion_main!(benches);
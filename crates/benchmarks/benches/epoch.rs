@@ -0,0 +1,54 @@
+use benchmarks::{
+    GoldenFunction, for_each_runtime, golden_functions, setup_golden, time_sync_calls,
+};
+use criterion::{Criterion, criterion_group, criterion_main};
+use engine::{EngineOptions, EpochTicker, Runtime};
+use std::time::Duration;
+
+/// Timeout of the calls with epoch checks, long enough to never expire
+const TIMEOUT: Duration = Duration::from_secs(3600);
+
+/// Benchmark `function` without and with epoch interruption in one group,
+/// the latter with an epoch ticker running and a timeout set
+///
+/// The gap between both is the cost of the epoch checks Cranelift inserts in
+/// function prologues and loop headers.
+fn benchmark_epoch<R: Runtime + 'static>(c: &mut Criterion, function: &GoldenFunction) {
+    let params: Vec<R::Val> = function.params.iter().cloned().map(R::Val::from).collect();
+    let params = params.as_slice();
+
+    let epoch_options = EngineOptions {
+        epoch_interruption: true,
+        ..Default::default()
+    };
+    let engines = [
+        (
+            "off",
+            setup_golden::<R>(function, &EngineOptions::default()),
+        ),
+        ("on", setup_golden::<R>(function, &epoch_options)),
+    ];
+    let (epoch_engine, _) = &engines[1].1;
+    let _ticker = EpochTicker::start::<R>(epoch_engine);
+
+    let mut group = c.benchmark_group(format!("call_epoch_{}_{}", function.label(), R::NAME));
+    for (epoch, (engine, pre_instance)) in &engines {
+        group.bench_function(*epoch, |b| {
+            b.iter_custom(|iters| {
+                let mut store = R::new_store(engine);
+                R::set_timeout(&mut store, TIMEOUT);
+                time_sync_calls::<R>(store, pre_instance, function, params, iters)
+            })
+        });
+    }
+    group.finish();
+}
+
+fn benchmark_epoch_all(c: &mut Criterion) {
+    for function in golden_functions() {
+        for_each_runtime!(R => benchmark_epoch::<R>(c, &function));
+    }
+}
+
+criterion_group!(benches, benchmark_epoch_all);
+criterion_main!(benches);
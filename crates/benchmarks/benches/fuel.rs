@@ -0,0 +1,82 @@
+use benchmarks::{
+    GoldenFunction, for_each_runtime, get_report_dir, golden_functions, setup_golden,
+    time_sync_calls,
+};
+use criterion::{Criterion, criterion_group, criterion_main};
+use engine::{EngineOptions, Runtime, Value};
+
+/// Call `function` once in a fresh store of a fuel-consuming engine, print
+/// the fuel consumed and save it as `target/fuel/<function>_<version>.json`
+/// for collect_report.py
+fn report_fuel<R: Runtime>(
+    engine: &R::Engine,
+    pre_instance: &R::InstancePre,
+    function: &GoldenFunction,
+    params: &[R::Val],
+) {
+    let mut store = R::new_store(engine);
+    let instance = R::instantiate(pre_instance, &mut store).expect("Instantiation failed");
+    let func = R::find_func(&instance, &mut store, function.name).expect("find func");
+    let mut results = vec![R::Val::from(Value::Bool(false)); R::results_len(&func, &store)];
+
+    let before = R::fuel(&store).expect("fuel enabled");
+    R::call(&func, &mut store, params, &mut results).expect("Call failed");
+    R::post_return(&func, &mut store).expect("unexpected error");
+    let fuel = before - R::fuel(&store).expect("fuel enabled");
+
+    let name = format!("{}_{}", function.label(), R::NAME);
+    println!("fuel_{name}: {fuel}");
+
+    let dir = get_report_dir("fuel");
+    std::fs::create_dir_all(&dir).expect("create fuel report dir");
+    let report = format!(
+        r#"{{"function": "{}", "version": "{}", "engine": "{}", "fuel": {fuel}}}"#,
+        function.label(),
+        R::NAME,
+        R::version(),
+    );
+    std::fs::write(dir.join(format!("{name}.json")), report).expect("write fuel report");
+}
+
+/// Benchmark `function` with fuel metering off and on in one group, after
+/// reporting the fuel one call consumes
+///
+/// The gap between both is the cost of the fuel checks Cranelift inserts.
+fn benchmark_fuel<R: Runtime>(c: &mut Criterion, function: &GoldenFunction) {
+    let params: Vec<R::Val> = function.params.iter().cloned().map(R::Val::from).collect();
+    let params = params.as_slice();
+
+    let fuel_options = EngineOptions {
+        consume_fuel: true,
+        ..Default::default()
+    };
+    let engines = [
+        (
+            "off",
+            setup_golden::<R>(function, &EngineOptions::default()),
+        ),
+        ("on", setup_golden::<R>(function, &fuel_options)),
+    ];
+
+    let (fuel_engine, fuel_pre) = &engines[1].1;
+    report_fuel::<R>(fuel_engine, fuel_pre, function, params);
+
+    let mut group = c.benchmark_group(format!("call_fuel_{}_{}", function.label(), R::NAME));
+    for (fuel, (engine, pre_instance)) in &engines {
+        group.bench_function(*fuel, |b| {
+            b.iter_custom(|iters| {
+                time_sync_calls::<R>(R::new_store(engine), pre_instance, function, params, iters)
+            })
+        });
+    }
+    group.finish();
+}
+
+fn benchmark_fuel_all(c: &mut Criterion) {
+    for function in golden_functions() {
+        for_each_runtime!(R => benchmark_fuel::<R>(c, &function));
+    }
+}
+
+criterion_group!(benches, benchmark_fuel_all);
+criterion_main!(benches);
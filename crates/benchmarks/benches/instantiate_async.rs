@@ -1,71 +1,39 @@
-use anyhow::{Context, Result};
+use benchmarks::{
+    GOLDEN_COMPONENTS, TOKIO_CONCURRENCY, for_each_runtime, get_golden_wasm_path,
+    golden_engine_options,
+};
 use criterion::async_executor::FuturesExecutor;
 use criterion::{Criterion, criterion_group, criterion_main};
-use engine::v21;
+use engine::{EngineOptions, Runtime};
 use std::hint::black_box;
-use std::path::PathBuf;
-
-use engine::v21::{
-    Config as ConfigV21, Engine as EngineV21, Store as StoreV21,
-    component::Component as ComponentV21, component::Linker as LinkerV21,
-};
-use engine::v41::wasi::p2::add_to_linker_sync as add_to_linker_sync_v41;
-use engine::v41::{
-    Config as ConfigV41, Engine as EngineV41, Store as StoreV41,
-    component::Component as ComponentV41, component::Linker as LinkerV41,
-};
-
-/// Load a WASM component file path from the golden/out directory
-fn get_golden_wasm_path(filename: &str) -> PathBuf {
-    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-    path.push("../golden/out");
-    path.push(filename);
-    path
-}
-
-/// Setup v21 engine and component for instantiation benchmark
-fn setup_engine_v21(path: &PathBuf) -> Result<(EngineV21, ComponentV21)> {
-    let mut config = ConfigV21::new();
-    config.wasm_component_model(true);
-    config.async_support(true);
-
-    let engine = EngineV21::new(&config).context("Failed to create v21 engine")?;
-    let component = ComponentV21::from_file(&engine, path)
-        .with_context(|| format!("Failed to load v21 component from: {}", path.display()))?;
-
-    Ok((engine, component))
-}
-
-/// Setup v41 engine and component for instantiation benchmark
-fn setup_engine_v41(path: &PathBuf) -> Result<(EngineV41, ComponentV41)> {
-    let mut config = ConfigV41::new();
-    config.wasm_component_model(true);
-    config.async_support(true);
-
-    let engine = EngineV41::new(&config).context("Failed to create v41 engine")?;
-    let component = ComponentV41::from_file(&engine, path)
-        .with_context(|| format!("Failed to load v41 component from: {}", path.display()))?;
-
-    Ok((engine, component))
-}
-
-/// Benchmark instantiation performance for v21 engine
-fn benchmark_instantiate_v21(c: &mut Criterion, wasm_file: &str) {
-    let wasm_path = get_golden_wasm_path(wasm_file);
-    let (engine, component) = setup_engine_v21(&wasm_path).expect("Setup v21 failed");
-    let mut linker = LinkerV21::new(&engine);
-
-    v21::wasi::add_to_linker_async(&mut linker).expect("link wasip1");
-
-    let pre_instance = linker.instantiate_pre(&component).expect("instantiate-pre");
-
-    let group_name = format!("instantiate_async_{}_v21", wasm_file.replace(".wasm", ""));
-    c.bench_function(&group_name, move |b| {
+use std::time::Instant;
+
+/// Benchmark async instantiation performance for the engine of runtime `R`
+fn benchmark_instantiate<R: Runtime>(c: &mut Criterion, wasm_file: &str, options: EngineOptions) {
+    if !options.profile.supported_by::<R>() {
+        return;
+    }
+    let options = EngineOptions {
+        async_support: true,
+        ..options
+    };
+    let engine = R::new_engine(&options).expect("Setup engine failed");
+    let component =
+        R::load_component(&engine, &get_golden_wasm_path(wasm_file)).expect("Setup failed");
+
+    let pre_instance = R::instantiate_pre(&engine, &component, &options).expect("instantiate-pre");
+
+    let group_name = format!(
+        "instantiate_async_{}_{}{}",
+        wasm_file.replace(".wasm", ""),
+        R::NAME,
+        options.suffix()
+    );
+    c.bench_function(&group_name, |b| {
         b.to_async(FuturesExecutor).iter(|| async {
-            let mut store = StoreV21::new(&engine, v21::WasiP2State::default());
+            let mut store = R::new_store(&engine);
             black_box(
-                pre_instance
-                    .instantiate_async(&mut store)
+                R::instantiate_async(&pre_instance, &mut store)
                     .await
                     .expect("Instantiation failed"),
             );
@@ -73,67 +41,99 @@ fn benchmark_instantiate_v21(c: &mut Criterion, wasm_file: &str) {
     });
 }
 
-/// Benchmark instantiation performance for v41 engine
-fn benchmark_instantiate_v41(c: &mut Criterion, wasm_file: &str) {
-    let wasm_path = get_golden_wasm_path(wasm_file);
-    let (engine, component) = setup_engine_v41(&wasm_path).expect("Setup v41 failed");
-    let mut linker = LinkerV41::new(&engine);
-
-    add_to_linker_sync_v41(&mut linker).expect("link wasip2");
-
-    let pre_instance = linker.instantiate_pre(&component).expect("instantiate-pre");
-
-    let group_name = format!("instantiate_async_{}_v41", wasm_file.replace(".wasm", ""));
+/// Benchmark async instantiation with `TOKIO_CONCURRENCY` tasks in flight on
+/// a Tokio multi-thread runtime for the engine of runtime `R`
+///
+/// The `iters` instantiations are split between the tasks, each into a fresh
+/// store, so the time per iteration is the wall time divided by all of them.
+fn benchmark_instantiate_tokio<R: Runtime + 'static>(c: &mut Criterion, wasm_file: &str) {
+    let options = EngineOptions {
+        async_support: true,
+        ..Default::default()
+    };
+    let engine = R::new_engine(&options).expect("Setup engine failed");
+    let component =
+        R::load_component(&engine, &get_golden_wasm_path(wasm_file)).expect("Setup failed");
+
+    let pre_instance = R::instantiate_pre(&engine, &component, &options).expect("instantiate-pre");
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .build()
+        .expect("Setup tokio runtime failed");
+
+    let group_name = format!(
+        "instantiate_async_tokio_{}_{}",
+        wasm_file.replace(".wasm", ""),
+        R::NAME
+    );
     c.bench_function(&group_name, |b| {
-        b.to_async(FuturesExecutor).iter(|| async {
-            let mut store = StoreV41::new(&engine, engine::v41::WasiP2State::default());
-            let _ii = black_box(
-                pre_instance
-                    .instantiate_async(&mut store)
-                    .await
-                    .expect("Instantiation failed"),
-            );
+        b.iter_custom(|iters| {
+            runtime.block_on(async {
+                let start = Instant::now();
+
+                let mut tasks = tokio::task::JoinSet::new();
+                for i in 0..TOKIO_CONCURRENCY as u64 {
+                    let engine = engine.clone();
+                    let pre_instance = pre_instance.clone();
+                    // spread the remainder over the first tasks
+                    let count = iters / TOKIO_CONCURRENCY as u64
+                        + u64::from(i < iters % TOKIO_CONCURRENCY as u64);
+                    tasks.spawn(async move {
+                        for _ in 0..count {
+                            let mut store = R::new_store(&engine);
+                            black_box(
+                                R::instantiate_async(&pre_instance, &mut store)
+                                    .await
+                                    .expect("Instantiation failed"),
+                            );
+                        }
+                    });
+                }
+                tasks.join_all().await;
+
+                start.elapsed()
+            })
         })
     });
 }
 
-/// Benchmark argon2.wasm instantiation with v21
-fn benchmark_instantiate_argon2_v21(c: &mut Criterion) {
-    benchmark_instantiate_v21(c, "argon2.wasm");
-}
-
-/// Benchmark argon2.wasm instantiation with v41
-fn benchmark_instantiate_argon2_v41(c: &mut Criterion) {
-    benchmark_instantiate_v41(c, "argon2.wasm");
-}
-
-/// Benchmark pulldown-cmark.wasm instantiation with v21
-fn benchmark_instantiate_pulldown_cmark_v21(c: &mut Criterion) {
-    benchmark_instantiate_v21(c, "pulldown-cmark.wasm");
+/// Benchmark every golden component instantiation on Tokio
+fn benchmark_instantiate_tokio_all(c: &mut Criterion) {
+    for wasm_file in GOLDEN_COMPONENTS {
+        for_each_runtime!(R => benchmark_instantiate_tokio::<R>(c, wasm_file));
+    }
 }
 
-/// Benchmark pulldown-cmark.wasm instantiation with v41
-fn benchmark_instantiate_pulldown_cmark_v41(c: &mut Criterion) {
-    benchmark_instantiate_v41(c, "pulldown-cmark.wasm");
+/// Benchmark argon2.wasm instantiation
+fn benchmark_instantiate_argon2(c: &mut Criterion) {
+    for options in golden_engine_options("argon2.wasm") {
+        for_each_runtime!(R => benchmark_instantiate::<R>(c, "argon2.wasm", options.clone()));
+    }
 }
 
-/// Benchmark sevenz-7z.wasm instantiation with v21
-fn benchmark_instantiate_sevenz_7z_v21(c: &mut Criterion) {
-    benchmark_instantiate_v21(c, "sevenz-7z.wasm");
+/// Benchmark pulldown-cmark.wasm instantiation
+fn benchmark_instantiate_pulldown_cmark(c: &mut Criterion) {
+    for options in golden_engine_options("pulldown-cmark.wasm") {
+        for_each_runtime!(R => benchmark_instantiate::<R>(
+            c,
+            "pulldown-cmark.wasm",
+            options.clone(),
+        ));
+    }
 }
 
-/// Benchmark sevenz-7z.wasm instantiation with v41
-fn benchmark_instantiate_sevenz_7z_v41(c: &mut Criterion) {
-    benchmark_instantiate_v41(c, "sevenz-7z.wasm");
+/// Benchmark sevenz-7z.wasm instantiation
+fn benchmark_instantiate_sevenz_7z(c: &mut Criterion) {
+    for options in golden_engine_options("sevenz-7z.wasm") {
+        for_each_runtime!(R => benchmark_instantiate::<R>(c, "sevenz-7z.wasm", options.clone()));
+    }
 }
 
 criterion_group!(
     benches,
-    benchmark_instantiate_argon2_v21,
-    benchmark_instantiate_argon2_v41,
-    benchmark_instantiate_pulldown_cmark_v21,
-    benchmark_instantiate_pulldown_cmark_v41,
-    benchmark_instantiate_sevenz_7z_v21,
-    benchmark_instantiate_sevenz_7z_v41
+    benchmark_instantiate_argon2,
+    benchmark_instantiate_pulldown_cmark,
+    benchmark_instantiate_sevenz_7z,
+    benchmark_instantiate_tokio_all
 );
 criterion_main!(benches);
@@ -0,0 +1,77 @@
+use benchmarks::{GOLDEN_COMPONENTS, for_each_runtime, get_golden_wasm_path, get_report_dir};
+use criterion::{Criterion, criterion_group, criterion_main};
+use engine::{EngineOptions, Runtime};
+use std::hint::black_box;
+
+/// Compare loading a golden component from source against loading its
+/// precompiled `.cwasm` artifact with runtime `R`
+///
+/// The artifact is written to `target/precompiled/<component>_<version>.cwasm`
+/// and its size saved next to it as JSON for collect_report.py.
+fn benchmark_precompiled<R: Runtime>(c: &mut Criterion, wasm_file: &str) {
+    let engine = R::new_engine(&EngineOptions::default()).expect("Setup engine failed");
+    let wasm_path = get_golden_wasm_path(wasm_file);
+    let name = wasm_file.replace(".wasm", "");
+
+    let wasm_bytes = std::fs::read(&wasm_path).expect("read component");
+    let artifact = R::precompile_component(&engine, &wasm_bytes).expect("precompile");
+
+    let dir = get_report_dir("precompiled");
+    std::fs::create_dir_all(&dir).expect("create precompiled dir");
+    let artifact_path = dir.join(format!("{name}_{}.cwasm", R::NAME));
+    std::fs::write(&artifact_path, &artifact).expect("write artifact");
+
+    println!(
+        "precompiled_{name}_{}: wasm {} bytes, cwasm {} bytes",
+        R::NAME,
+        wasm_bytes.len(),
+        artifact.len()
+    );
+    let report = format!(
+        r#"{{"component": "{name}", "version": "{}", "wasm_size": {}, "cwasm_size": {}}}"#,
+        R::NAME,
+        wasm_bytes.len(),
+        artifact.len()
+    );
+    std::fs::write(dir.join(format!("{name}_{}.json", R::NAME)), report)
+        .expect("write artifact report");
+
+    c.bench_function(&format!("load_from_file_{name}_{}", R::NAME), |b| {
+        b.iter(|| black_box(R::load_component(&engine, &wasm_path).expect("Load failed")))
+    });
+
+    // SAFETY: the artifact was just produced by the same engine version
+    c.bench_function(&format!("load_deserialize_{name}_{}", R::NAME), |b| {
+        b.iter(|| {
+            black_box(
+                unsafe { R::deserialize_component(&engine, &artifact) }
+                    .expect("Deserialization failed"),
+            )
+        })
+    });
+
+    // SAFETY: as above, and nothing writes the file while benchmarking
+    c.bench_function(&format!("load_deserialize_file_{name}_{}", R::NAME), |b| {
+        b.iter(|| {
+            black_box(
+                unsafe { R::deserialize_component_file(&engine, &artifact_path) }
+                    .expect("Deserialization failed"),
+            )
+        })
+    });
+
+    let component = R::load_component(&engine, &wasm_path).expect("Load failed");
+    c.bench_function(&format!("serialize_{name}_{}", R::NAME), |b| {
+        b.iter(|| black_box(R::serialize_component(&component).expect("Serialization failed")))
+    });
+}
+
+/// Benchmark precompiled artifacts of every golden component
+fn benchmark_precompiled_golden(c: &mut Criterion) {
+    for wasm_file in GOLDEN_COMPONENTS {
+        for_each_runtime!(R => benchmark_precompiled::<R>(c, wasm_file));
+    }
+}
+
+criterion_group!(benches, benchmark_precompiled_golden);
+criterion_main!(benches);
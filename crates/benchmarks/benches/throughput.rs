@@ -0,0 +1,149 @@
+use benchmarks::{
+    GoldenFunction, for_each_runtime, get_golden_wasm_path, get_report_dir, golden_functions,
+    thread_counts,
+};
+use criterion::{Criterion, criterion_group, criterion_main};
+use engine::{EngineOptions, Runtime, Value};
+use std::hint::black_box;
+use std::sync::Barrier;
+use std::time::{Duration, Instant};
+
+/// Wall time each thread count is measured for in the throughput report
+const REPORT_WINDOW: Duration = Duration::from_secs(1);
+
+/// Call `function` `calls` times on each of `threads` workers sharing one
+/// engine and `InstancePre`, and return the wall time of the calls
+///
+/// Every worker gets a store instantiated before the threads are spawned, so
+/// a failing setup panics here instead of leaving the other workers waiting.
+/// A failing call panics its worker, which `join` passes on.
+fn run_parallel<R: Runtime>(
+    engine: &R::Engine,
+    pre_instance: &R::InstancePre,
+    function: &GoldenFunction,
+    params: &[R::Val],
+    threads: usize,
+    calls: u64,
+) -> Duration {
+    let workers: Vec<_> = (0..threads)
+        .map(|_| {
+            let mut store = R::new_store(engine);
+            let instance = R::instantiate(pre_instance, &mut store).expect("Instantiation failed");
+            let func = R::find_func(&instance, &mut store, function.name).expect("find func");
+            let results = vec![R::Val::from(Value::Bool(false)); R::results_len(&func, &store)];
+            (store, func, results)
+        })
+        .collect();
+    let ready = Barrier::new(threads + 1);
+
+    std::thread::scope(|s| {
+        let handles: Vec<_> = workers
+            .into_iter()
+            .map(|(mut store, func, mut results)| {
+                let ready = &ready;
+                s.spawn(move || {
+                    ready.wait();
+                    for _ in 0..calls {
+                        R::call(&func, &mut store, params, &mut results).expect("Call failed");
+                        R::post_return(&func, &mut store).expect("unexpected error");
+                        black_box(&results);
+                    }
+                })
+            })
+            .collect();
+
+        ready.wait();
+        let start = Instant::now();
+        for handle in handles {
+            handle.join().expect("worker panicked");
+        }
+        start.elapsed()
+    })
+}
+
+/// Measure calls per second for every thread count, print them with the
+/// scaling efficiency and save them as `target/throughput/<function>_<version>.json`
+/// for collect_report.py
+///
+/// The efficiency is the throughput of N threads over N times the throughput
+/// of one thread.
+fn report_throughput<R: Runtime>(
+    engine: &R::Engine,
+    pre_instance: &R::InstancePre,
+    function: &GoldenFunction,
+    params: &[R::Val],
+) {
+    // calibrate the calls per thread so one measurement lasts about REPORT_WINDOW
+    let single = run_parallel::<R>(engine, pre_instance, function, params, 1, 1);
+    let calls = (REPORT_WINDOW.as_secs_f64() / single.as_secs_f64()).max(1.0) as u64;
+
+    let name = format!("{}_{}", function.label(), R::NAME);
+    let mut single_thread = None;
+    let mut entries = vec![];
+    for threads in thread_counts() {
+        let elapsed = run_parallel::<R>(engine, pre_instance, function, params, threads, calls);
+        let calls_per_sec = (threads as u64 * calls) as f64 / elapsed.as_secs_f64();
+        let baseline = *single_thread.get_or_insert(calls_per_sec);
+        let efficiency = calls_per_sec / (threads as f64 * baseline);
+
+        println!(
+            "throughput_{name}_t{threads}: {calls_per_sec:.2} calls/s, efficiency {:.1}%",
+            efficiency * 100.0
+        );
+        entries.push(format!(
+            r#"{{"threads": {threads}, "calls_per_sec": {calls_per_sec}, "efficiency": {efficiency}}}"#
+        ));
+    }
+
+    let dir = get_report_dir("throughput");
+    std::fs::create_dir_all(&dir).expect("create throughput report dir");
+    let report = format!(
+        r#"{{"function": "{}", "version": "{}", "engine": "{}", "results": [{}]}}"#,
+        function.label(),
+        R::NAME,
+        R::version(),
+        entries.join(", ")
+    );
+    std::fs::write(dir.join(format!("{name}.json")), report).expect("write throughput report");
+}
+
+/// Benchmark `function` called concurrently by a growing number of threads
+/// with runtime `R`
+///
+/// One criterion iteration is one call on every thread, so the time per
+/// iteration stays flat when the engine scales perfectly.
+fn benchmark_throughput<R: Runtime>(c: &mut Criterion, function: &GoldenFunction) {
+    let options = EngineOptions::default();
+    let engine = R::new_engine(&options).expect("Setup engine failed");
+    let component = R::load_component(&engine, &get_golden_wasm_path(function.wasm_file))
+        .expect("Setup failed");
+    let pre_instance = R::instantiate_pre(&engine, &component, &options).expect("instantiate-pre");
+    let params: Vec<R::Val> = function.params.iter().cloned().map(R::Val::from).collect();
+
+    report_throughput::<R>(&engine, &pre_instance, function, &params);
+
+    for threads in thread_counts() {
+        c.bench_function(
+            &format!("throughput_{}_{}_t{threads}", function.label(), R::NAME),
+            |b| {
+                b.iter_custom(|iters| {
+                    run_parallel::<R>(&engine, &pre_instance, function, &params, threads, iters)
+                })
+            },
+        );
+    }
+}
+
+fn benchmark_throughput_all(c: &mut Criterion) {
+    for function in golden_functions() {
+        for_each_runtime!(R => benchmark_throughput::<R>(c, &function));
+    }
+}
+
+criterion_group!(
+    name = benches;
+    // argon2 and sevenz-7z calls take tens of milliseconds on every thread
+    config = Criterion::default().sample_size(10);
+    targets = benchmark_throughput_all
+);
+criterion_main!(benches);
@@ -0,0 +1,94 @@
+use benchmarks::{GoldenFunction, golden_functions, setup_golden, time_sync_calls};
+use criterion::{Criterion, criterion_group, criterion_main};
+use engine::v21::V21;
+use engine::v41::V41;
+use engine::{EngineError, EngineOptions, TypedRuntime};
+use std::hint::black_box;
+use std::time::Instant;
+
+/// Benchmark `function` through the dynamic `Func::call` with `Val`s and
+/// through its `bindgen!` binding, side by side in one group
+///
+/// # Arguments
+/// * `bind` - Look up the typed exports of the component in an instance
+/// * `call` - Call the function once through the typed exports
+fn benchmark_typed_vs_dynamic<R: TypedRuntime, W>(
+    c: &mut Criterion,
+    function: &GoldenFunction,
+    bind: fn(&R::Instance, &mut R::Store) -> Result<W, EngineError>,
+    call: impl Fn(&W, &mut R::Store),
+) {
+    let (engine, pre_instance) = setup_golden::<R>(function, &EngineOptions::default());
+
+    let params: Vec<R::Val> = function.params.iter().cloned().map(R::Val::from).collect();
+    let params = params.as_slice();
+
+    let mut group = c.benchmark_group(format!("call_typed_{}_{}", function.label(), R::NAME));
+
+    group.bench_function("dynamic", |b| {
+        b.iter_custom(|iters| {
+            time_sync_calls::<R>(
+                R::new_store(&engine),
+                &pre_instance,
+                function,
+                params,
+                iters,
+            )
+        })
+    });
+
+    group.bench_function("typed", |b| {
+        b.iter_custom(|iters| {
+            let mut store = R::new_store(&engine);
+            let instance = R::instantiate(&pre_instance, &mut store).expect("Instantiation failed");
+            let world = bind(&instance, &mut store).expect("bind typed exports");
+
+            let start = Instant::now();
+
+            for _ in 0..iters {
+                call(&world, &mut store);
+            }
+
+            start.elapsed()
+        })
+    });
+
+    group.finish();
+}
+
+/// Benchmark every golden function with runtime `R`, the typed arguments are
+/// the same as the `Value`s of `golden_functions`
+fn benchmark_typed<R: TypedRuntime>(c: &mut Criterion) {
+    let bytes: Vec<u8> = (0..=255).cycle().take(1024).collect();
+
+    for function in golden_functions() {
+        match function.wasm_file {
+            "argon2.wasm" => benchmark_typed_vs_dynamic::<R, _>(c, &function, R::argon2, |w, s| {
+                black_box(
+                    R::argon2_hash(w, s, &bytes, "hello-world-hello-world").expect("Call failed"),
+                );
+            }),
+            "pulldown-cmark.wasm" => {
+                let markdown = "Hello world, this is a ~~complicated~~ *very simple* example.";
+                benchmark_typed_vs_dynamic::<R, _>(c, &function, R::pulldown_cmark, |w, s| {
+                    black_box(R::pulldown_cmark_parse(w, s, markdown).expect("Call failed"));
+                })
+            }
+            "sevenz-7z.wasm" => {
+                benchmark_typed_vs_dynamic::<R, _>(c, &function, R::sevenz_7z, |w, s| {
+                    black_box(R::sevenz_7z_zip(w, s, &bytes).expect("Call failed"));
+                })
+            }
+            other => unreachable!("no typed binding for {other}"),
+        }
+    }
+}
+
+// Only v21 and v41 implement `TypedRuntime`
+fn benchmark_typed_all(c: &mut Criterion) {
+    benchmark_typed::<V21>(c);
+    benchmark_typed::<V41>(c);
+}
+
+criterion_group!(benches, benchmark_typed_all);
+criterion_main!(benches);
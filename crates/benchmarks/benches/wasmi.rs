@@ -0,0 +1,98 @@
+use benchmarks::{WASMI_EXECUTABLE, erc20_input, for_each_runtime, wasmi_input_dir};
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+use engine::{EngineOptions, Runtime};
+use std::hint::black_box;
+use testdata::wasmi;
+
+/// Benchmark instantiation of a wasmi-benchmarks module
+///
+/// Compilation is covered by `benches/compile.rs`.
+fn benchmark_wasmi_module<R: Runtime>(c: &mut Criterion, name: &str, wasm_bytes: &[u8]) {
+    let engine = R::new_engine(&EngineOptions::default()).expect("Setup engine failed");
+    let module = R::load_module(&engine, wasm_bytes).expect("Compilation failed");
+    let pre_instance = R::core_instance_pre(&engine, &module).expect("instantiate-pre");
+
+    c.bench_function(&format!("wasmi_instantiate_{name}_{}", R::NAME), |b| {
+        b.iter(|| {
+            let mut store = R::new_core_store(&engine, None).expect("new store");
+            black_box(
+                R::instantiate_core(&pre_instance, &mut store).expect("Instantiation failed"),
+            );
+        })
+    });
+}
+
+/// Benchmark running the entry point of a wasmi-benchmarks module
+///
+/// Every iteration gets a fresh store and instance, only the call is timed.
+/// The `call` of erc20 runs against a contract deployed in the same store.
+fn benchmark_wasmi_execute<R: Runtime>(
+    c: &mut Criterion,
+    name: &str,
+    wasm_bytes: &[u8],
+    func_name: &str,
+    args: &[i64],
+) {
+    let engine = R::new_engine(&EngineOptions::default()).expect("Setup engine failed");
+    let module = R::load_module(&engine, wasm_bytes).expect("Compilation failed");
+    let pre_instance = R::core_instance_pre(&engine, &module).expect("instantiate-pre");
+
+    let input_dir = wasmi_input_dir().expect("prepare input files");
+    let args: Vec<R::CoreVal> = args.iter().copied().map(R::CoreVal::from).collect();
+
+    c.bench_function(&format!("wasmi_execute_{name}_{}", R::NAME), |b| {
+        b.iter_batched(
+            || {
+                let mut store = R::new_core_store(&engine, Some(&input_dir)).expect("new store");
+                let instance =
+                    R::instantiate_core(&pre_instance, &mut store).expect("Instantiation failed");
+                if name == "erc20" {
+                    if func_name == "call" {
+                        R::set_contract_input(&mut store, erc20_input("deploy"));
+                        R::call_core(&instance, &mut store, "deploy", &[]).expect("deploy erc20");
+                    }
+                    R::set_contract_input(&mut store, erc20_input(func_name));
+                }
+                (store, instance)
+            },
+            |(mut store, instance)| {
+                black_box(
+                    R::call_core(&instance, &mut store, func_name, &args).expect("Call failed"),
+                )
+            },
+            BatchSize::PerIteration,
+        )
+    });
+}
+
+/// Benchmark instantiation of every wasmi-benchmarks module
+fn benchmark_wasmi_modules(c: &mut Criterion) {
+    for (name, wasm_bytes) in wasmi::ALL {
+        for_each_runtime!(R => benchmark_wasmi_module::<R>(c, name, wasm_bytes));
+    }
+}
+
+/// Benchmark execution of the runnable wasmi-benchmarks modules
+fn benchmark_wasmi_executables(c: &mut Criterion) {
+    for (name, func_name, args) in WASMI_EXECUTABLE {
+        let (_, wasm_bytes) = wasmi::ALL
+            .iter()
+            .find(|(n, _)| n == name)
+            .expect("unknown wasmi module");
+        let wasm_bytes = match *name {
+            "erc20" => engine::contract::export_memory(wasm_bytes).expect("export erc20 memory"),
+            _ => wasm_bytes.to_vec(),
+        };
+
+        for_each_runtime!(R => benchmark_wasmi_execute::<R>(c, name, &wasm_bytes, func_name, args));
+    }
+}
+
+criterion_group!(benches, benchmark_wasmi_modules);
+criterion_group!(
+    name = execute_benches;
+    // A single run of coremark or bz2 takes seconds
+    config = Criterion::default().sample_size(10);
+    targets = benchmark_wasmi_executables
+);
+criterion_main!(benches, execute_benches);
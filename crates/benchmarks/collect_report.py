@@ -10,7 +10,7 @@ import subprocess
 import sys
 from dataclasses import dataclass
 from pathlib import Path
-from typing import List, Optional
+from typing import Dict, List, Optional
 
 
 def get_system_info() -> str:
@@ -118,6 +118,7 @@ def parse_estimates_json(json_path: Path) -> Optional[BenchmarkResult]:
 
         # Extract benchmark name from directory path
         # Path format: target/criterion/<benchmark_name>/new/estimates.json
+        # or target/criterion/<group>/<function>/new/estimates.json
         parts = json_path.parts
         criterion_idx = -1
         for i, part in enumerate(parts):
@@ -125,8 +126,8 @@ def parse_estimates_json(json_path: Path) -> Optional[BenchmarkResult]:
                 criterion_idx = i
                 break
 
-        if criterion_idx >= 0 and criterion_idx + 1 < len(parts):
-            benchmark_name = parts[criterion_idx + 1]
+        if criterion_idx >= 0 and criterion_idx + 1 < len(parts) - 2:
+            benchmark_name = "/".join(parts[criterion_idx + 1:-2])
         else:
             benchmark_name = json_path.parent.parent.name
 
@@ -163,7 +164,333 @@ def find_estimates_files(base_path: Path) -> List[Path]:
     """
     # Search for target/criterion/**/new/estimates.json
     search_pattern = base_path / "target" / "criterion" / "*" / "new" / "estimates.json"
-    return list(Path(base_path).glob("target/criterion/*/new/estimates.json"))
+    files = list(Path(base_path).glob("target/criterion/*/new/estimates.json"))
+    # Groups comparing functions side by side, e.g. call_<function>_<version>/{sync,async}
+    files += Path(base_path).glob("target/criterion/call_*/*/new/estimates.json")
+    return files
+
+
+@dataclass
+class CoremarkScore:
+    """CoreMark score data class."""
+
+    version: str
+    engine: str
+    score: float
+
+
+def find_coremark_scores(base_path: Path) -> List[CoremarkScore]:
+    """Load CoreMark scores written by benches/coremark.rs.
+
+    Args:
+        base_path: Base directory containing target/coremark.
+
+    Returns:
+        List of CoreMark scores sorted by version.
+    """
+    scores = []
+    for json_path in sorted(Path(base_path).glob("target/coremark/*.json")):
+        try:
+            with open(json_path, "r", encoding="utf-8") as f:
+                data = json.load(f)
+            scores.append(CoremarkScore(
+                version=data["version"],
+                engine=data["engine"],
+                score=data["score"]
+            ))
+        except (json.JSONDecodeError, KeyError, IOError) as e:
+            print(f"Error parsing {json_path}: {e}", file=sys.stderr)
+
+    return scores
+
+
+def get_cpu_mhz() -> Optional[float]:
+    """Get the current CPU frequency in MHz from /proc/cpuinfo.
+
+    Returns:
+        CPU frequency in MHz, None if unavailable.
+    """
+    try:
+        with open("/proc/cpuinfo", "r") as f:
+            for line in f:
+                if line.startswith("cpu MHz"):
+                    return float(line.split(":", 1)[1].strip())
+    except (IOError, ValueError):
+        pass
+
+    return None
+
+
+def generate_coremark_table(scores: List[CoremarkScore]) -> str:
+    """Generate markdown table from CoreMark scores.
+
+    Args:
+        scores: List of CoreMark scores.
+
+    Returns:
+        Markdown table string, empty if there is no score.
+    """
+    if not scores:
+        return ""
+
+    mhz = get_cpu_mhz()
+
+    table = []
+    table.append("## CoreMark 得分")
+    table.append("| 版本 | 引擎 | 得分（迭代/秒） | 得分/MHz |")
+    table.append("|------|------|-----------------|----------|")
+
+    for score in scores:
+        per_mhz = f"{score.score / mhz:.4f}" if mhz else "-"
+        table.append(f"| {score.version} | {score.engine} | {score.score:.2f} | {per_mhz} |")
+
+    return "\n".join(table)
+
+
+@dataclass
+class ColdStart:
+    """Cold start phase breakdown data class."""
+
+    function: str
+    version: str
+    phases_us: Dict[str, float]
+
+
+COLD_START_PHASES = ["load", "link", "instantiate_pre", "instantiate", "first_call", "total"]
+
+
+def find_cold_starts(base_path: Path) -> List[ColdStart]:
+    """Load cold start breakdowns written by benches/cold_start.rs.
+
+    Args:
+        base_path: Base directory containing target/cold_start.
+
+    Returns:
+        List of cold start breakdowns sorted by function and version.
+    """
+    cold_starts = []
+    for json_path in sorted(Path(base_path).glob("target/cold_start/*.json")):
+        try:
+            with open(json_path, "r", encoding="utf-8") as f:
+                data = json.load(f)
+            cold_starts.append(ColdStart(
+                function=data["function"],
+                version=data["version"],
+                phases_us=data["phases_us"]
+            ))
+        except (json.JSONDecodeError, KeyError, IOError) as e:
+            print(f"Error parsing {json_path}: {e}", file=sys.stderr)
+
+    return cold_starts
+
+
+def generate_cold_start_table(cold_starts: List[ColdStart]) -> str:
+    """Generate markdown table from cold start breakdowns.
+
+    Args:
+        cold_starts: List of cold start breakdowns.
+
+    Returns:
+        Markdown table string, empty if there is no breakdown.
+    """
+    if not cold_starts:
+        return ""
+
+    table = []
+    table.append("## 冷启动耗时拆解（µs，中位数）")
+    table.append("| 函数 | 版本 | " + " | ".join(COLD_START_PHASES) + " |")
+    table.append("|------|------|" + "|".join("---" for _ in COLD_START_PHASES) + "|")
+
+    for cold_start in cold_starts:
+        phases = " | ".join(
+            f"{cold_start.phases_us.get(phase, 0):.1f}" for phase in COLD_START_PHASES
+        )
+        table.append(f"| {cold_start.function} | {cold_start.version} | {phases} |")
+
+    return "\n".join(table)
+
+
+@dataclass
+class Fuel:
+    """Fuel consumed by one call data class."""
+
+    function: str
+    version: str
+    fuel: int
+
+
+def find_fuels(base_path: Path) -> List[Fuel]:
+    """Load fuel consumption written by benches/fuel.rs.
+
+    Args:
+        base_path: Base directory containing target/fuel.
+
+    Returns:
+        List of fuel consumption sorted by function and version.
+    """
+    fuels = []
+    for json_path in sorted(Path(base_path).glob("target/fuel/*.json")):
+        try:
+            with open(json_path, "r", encoding="utf-8") as f:
+                data = json.load(f)
+            fuels.append(Fuel(
+                function=data["function"],
+                version=data["version"],
+                fuel=data["fuel"]
+            ))
+        except (json.JSONDecodeError, KeyError, IOError) as e:
+            print(f"Error parsing {json_path}: {e}", file=sys.stderr)
+
+    return fuels
+
+
+def generate_fuel_table(fuels: List[Fuel]) -> str:
+    """Generate markdown table from fuel consumption.
+
+    Args:
+        fuels: List of fuel consumption.
+
+    Returns:
+        Markdown table string, empty if there is no fuel consumption.
+    """
+    if not fuels:
+        return ""
+
+    table = []
+    table.append("## 每次调用消耗的燃料")
+    table.append("| 函数 | 版本 | 燃料 |")
+    table.append("|------|------|------|")
+
+    for fuel in fuels:
+        table.append(f"| {fuel.function} | {fuel.version} | {fuel.fuel} |")
+
+    return "\n".join(table)
+
+
+@dataclass
+class Throughput:
+    """Multi-threaded throughput data class."""
+
+    function: str
+    version: str
+    threads: int
+    calls_per_sec: float
+    efficiency: float
+
+
+def find_throughputs(base_path: Path) -> List[Throughput]:
+    """Load throughput results written by benches/throughput.rs.
+
+    Args:
+        base_path: Base directory containing target/throughput.
+
+    Returns:
+        List of throughput results sorted by function, version and threads.
+    """
+    throughputs = []
+    for json_path in sorted(Path(base_path).glob("target/throughput/*.json")):
+        try:
+            with open(json_path, "r", encoding="utf-8") as f:
+                data = json.load(f)
+            for result in data["results"]:
+                throughputs.append(Throughput(
+                    function=data["function"],
+                    version=data["version"],
+                    threads=result["threads"],
+                    calls_per_sec=result["calls_per_sec"],
+                    efficiency=result["efficiency"]
+                ))
+        except (json.JSONDecodeError, KeyError, IOError) as e:
+            print(f"Error parsing {json_path}: {e}", file=sys.stderr)
+
+    return throughputs
+
+
+def generate_throughput_table(throughputs: List[Throughput]) -> str:
+    """Generate markdown table from throughput results.
+
+    Args:
+        throughputs: List of throughput results.
+
+    Returns:
+        Markdown table string, empty if there is no result.
+    """
+    if not throughputs:
+        return ""
+
+    table = []
+    table.append("## 多线程吞吐")
+    table.append("| 函数 | 版本 | 线程数 | 调用/秒 | 扩展效率 |")
+    table.append("|------|------|--------|---------|----------|")
+
+    for t in throughputs:
+        table.append(
+            f"| {t.function} | {t.version} | {t.threads} | {t.calls_per_sec:.2f} | {t.efficiency:.1%} |"
+        )
+
+    return "\n".join(table)
+
+
+@dataclass
+class ArtifactSize:
+    """Precompiled artifact size data class."""
+
+    component: str
+    version: str
+    wasm_size: int
+    cwasm_size: int
+
+
+def find_artifact_sizes(base_path: Path) -> List[ArtifactSize]:
+    """Load artifact sizes written by benches/precompiled.rs.
+
+    Args:
+        base_path: Base directory containing target/precompiled.
+
+    Returns:
+        List of artifact sizes sorted by component and version.
+    """
+    sizes = []
+    for json_path in sorted(Path(base_path).glob("target/precompiled/*.json")):
+        try:
+            with open(json_path, "r", encoding="utf-8") as f:
+                data = json.load(f)
+            sizes.append(ArtifactSize(
+                component=data["component"],
+                version=data["version"],
+                wasm_size=data["wasm_size"],
+                cwasm_size=data["cwasm_size"]
+            ))
+        except (json.JSONDecodeError, KeyError, IOError) as e:
+            print(f"Error parsing {json_path}: {e}", file=sys.stderr)
+
+    return sizes
+
+
+def generate_artifact_table(sizes: List[ArtifactSize]) -> str:
+    """Generate markdown table from precompiled artifact sizes.
+
+    Args:
+        sizes: List of artifact sizes.
+
+    Returns:
+        Markdown table string, empty if there is no artifact.
+    """
+    if not sizes:
+        return ""
+
+    table = []
+    table.append("## 预编译制品大小")
+    table.append("| 组件 | 版本 | wasm（字节） | cwasm（字节） | 膨胀倍数 |")
+    table.append("|------|------|--------------|---------------|----------|")
+
+    for size in sizes:
+        ratio = size.cwasm_size / size.wasm_size if size.wasm_size else 0
+        table.append(
+            f"| {size.component} | {size.version} | {size.wasm_size} | {size.cwasm_size} | {ratio:.2f} |"
+        )
+
+    return "\n".join(table)
 
 
 def generate_markdown_table(results: List[BenchmarkResult]) -> str:
@@ -236,6 +563,26 @@ def main():
     # Generate and output table
     markdown_table = generate_markdown_table(results)
 
+    coremark_table = generate_coremark_table(find_coremark_scores(base_path))
+    if coremark_table:
+        markdown_table += "\n\n" + coremark_table
+
+    cold_start_table = generate_cold_start_table(find_cold_starts(base_path))
+    if cold_start_table:
+        markdown_table += "\n\n" + cold_start_table
+
+    throughput_table = generate_throughput_table(find_throughputs(base_path))
+    if throughput_table:
+        markdown_table += "\n\n" + throughput_table
+
+    fuel_table = generate_fuel_table(find_fuels(base_path))
+    if fuel_table:
+        markdown_table += "\n\n" + fuel_table
+
+    artifact_table = generate_artifact_table(find_artifact_sizes(base_path))
+    if artifact_table:
+        markdown_table += "\n\n" + artifact_table
+
     if args.output:
         with open(args.output, "w", encoding="utf-8") as f:
             f.write(markdown_table)
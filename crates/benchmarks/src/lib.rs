@@ -1,2 +1,292 @@
 // This crate contains benchmark implementations
 // The actual benchmarks are in the benches/ directory
+use anyhow::{Context, Result};
+use engine::{Allocator, EngineOptions, PoolingLimits, Profile, Runtime, Value};
+use std::hint::black_box;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Components built into the golden/out directory by `crates/golden/Makefile`
+pub const GOLDEN_COMPONENTS: &[&str] = &["argon2.wasm", "pulldown-cmark.wasm", "sevenz-7z.wasm"];
+
+/// Exported function of a golden component with the arguments benchmarks
+/// call it with
+pub struct GoldenFunction {
+    /// Component file under golden/out
+    pub wasm_file: &'static str,
+    /// Function name in form of "interface#function"
+    pub name: &'static str,
+    /// Arguments of every call
+    pub params: Vec<Value>,
+}
+
+impl GoldenFunction {
+    /// Label used in benchmark names, e.g. "argon2_hash"
+    pub fn label(&self) -> String {
+        let (_, func) = self.name.rsplit_once('#').unwrap_or(("", self.name));
+        format!("{}_{func}", self.wasm_file.trim_end_matches(".wasm"))
+    }
+}
+
+/// The golden functions every end-to-end benchmark calls
+pub fn golden_functions() -> Vec<GoldenFunction> {
+    vec![
+        GoldenFunction {
+            wasm_file: "argon2.wasm",
+            name: "sammyne:argon2/api@1.0.0#hash",
+            params: vec![
+                Value::bytes((0..=255).cycle().take(1024)),
+                Value::from("hello-world-hello-world"),
+            ],
+        },
+        GoldenFunction {
+            wasm_file: "pulldown-cmark.wasm",
+            name: "sammyne:pulldown-cmark/api@1.0.0#parse",
+            params: vec![Value::from(
+                "Hello world, this is a ~~complicated~~ *very simple* example.",
+            )],
+        },
+        GoldenFunction {
+            wasm_file: "sevenz-7z.wasm",
+            name: "sammyne:sevenz7z/api@1.0.0#zip",
+            params: vec![Value::bytes((0..=255).cycle().take(1024))],
+        },
+    ]
+}
+
+/// Number of instances in flight at once in the Tokio async benchmarks
+pub const TOKIO_CONCURRENCY: usize = 32;
+
+/// Worker thread counts of the throughput benchmark: powers of two up to the
+/// available parallelism, which is always included
+pub fn thread_counts() -> Vec<usize> {
+    let max = std::thread::available_parallelism().map_or(1, |n| n.get());
+
+    let mut counts: Vec<usize> = std::iter::successors(Some(1), |n| Some(n * 2))
+        .take_while(|n| *n < max)
+        .collect();
+    counts.push(max);
+    counts
+}
+
+/// Load a WASM component file path from the golden/out directory
+pub fn get_golden_wasm_path(filename: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("../golden/out");
+    path.push(filename);
+    path
+}
+
+/// Evaluate an expression once for every wasmtime version enabled, with
+/// `$r` bound to its `Runtime` type
+///
+/// v21 and v41 are always enabled, the versions in between are behind the
+/// `v25`, `v29`, `v33` and `v37` features of this crate.
+///
+/// # Example
+/// ```ignore
+/// for_each_runtime!(R => benchmark_instantiate::<R>(c, "argon2.wasm"));
+/// ```
+#[macro_export]
+macro_rules! for_each_runtime {
+    ($r:ident => $body:expr) => {{
+        {
+            type $r = ::engine::v21::V21;
+            $body;
+        }
+        #[cfg(feature = "v25")]
+        {
+            type $r = ::engine::v25::V25;
+            $body;
+        }
+        #[cfg(feature = "v29")]
+        {
+            type $r = ::engine::v29::V29;
+            $body;
+        }
+        #[cfg(feature = "v33")]
+        {
+            type $r = ::engine::v33::V33;
+            $body;
+        }
+        #[cfg(feature = "v37")]
+        {
+            type $r = ::engine::v37::V37;
+            $body;
+        }
+        {
+            type $r = ::engine::v41::V41;
+            $body;
+        }
+    }};
+}
+
+/// Pooling allocator limits for a golden component
+///
+/// The defaults fit every component's instance layout, only the linear
+/// memory size differs with how much the guest allocates per call.
+pub fn golden_pooling_limits(wasm_file: &str) -> PoolingLimits {
+    let max_memory_size = match wasm_file {
+        // LZMA encoder state grows with its dictionary size
+        "sevenz-7z.wasm" => 512 << 20,
+        // enough for the rest, e.g. argon2 hashing with the default m_cost of 19 MiB
+        _ => 64 << 20,
+    };
+
+    PoolingLimits {
+        max_memory_size,
+        ..Default::default()
+    }
+}
+
+/// Engine options every instantiate and call benchmark runs with
+///
+/// Each profile of `Profile::matrix` with the on-demand allocator, plus the
+/// default profile with the pooling allocator. Profiles a runtime doesn't
+/// support are skipped by the benchmarks.
+pub fn golden_engine_options(wasm_file: &str) -> Vec<EngineOptions> {
+    let mut options: Vec<_> = Profile::matrix()
+        .into_iter()
+        .map(|profile| EngineOptions {
+            profile,
+            ..Default::default()
+        })
+        .collect();
+    options.push(EngineOptions {
+        allocator: Allocator::Pooling(golden_pooling_limits(wasm_file)),
+        ..Default::default()
+    });
+    options
+}
+
+/// Create an engine with `options` and pre-instantiate the component of
+/// `function` on it
+pub fn setup_golden<R: Runtime>(
+    function: &GoldenFunction,
+    options: &EngineOptions,
+) -> (R::Engine, R::InstancePre) {
+    let engine = R::new_engine(options).expect("Setup engine failed");
+    let component = R::load_component(&engine, &get_golden_wasm_path(function.wasm_file))
+        .expect("Setup failed");
+    let pre_instance = R::instantiate_pre(&engine, &component, options).expect("instantiate-pre");
+
+    (engine, pre_instance)
+}
+
+/// Instantiate `pre_instance` in `store`, then call `function` `iters`
+/// times through `Func::call` and return the time the calls took
+///
+/// Meant for `iter_custom`: instantiation and the lookup of the function
+/// aren't timed.
+pub fn time_sync_calls<R: Runtime>(
+    mut store: R::Store,
+    pre_instance: &R::InstancePre,
+    function: &GoldenFunction,
+    params: &[R::Val],
+    iters: u64,
+) -> Duration {
+    let instance = R::instantiate(pre_instance, &mut store).expect("Instantiation failed");
+    let func = R::find_func(&instance, &mut store, function.name).expect("find func");
+    let mut results = vec![R::Val::from(Value::Bool(false)); R::results_len(&func, &store)];
+
+    let start = Instant::now();
+
+    for _ in 0..iters {
+        R::call(&func, &mut store, params, &mut results).expect("Call failed");
+        black_box(R::post_return(&func, &mut store).expect("unexpected error"));
+    }
+
+    start.elapsed()
+}
+
+/// wasmi-benchmarks modules which run to completion with the host imports of
+/// `core_linker`, with their entry point and `i64` arguments
+///
+/// erc20 runs against the contract host of [`engine::contract`], `deploy`
+/// and `call` getting their input from [`erc20_input`]. ffmpeg and
+/// spidermonkey are only compiled and instantiated, like wasmi-benchmarks
+/// does: their `_start` needs a video or a script plus command line
+/// arguments we don't ship. coremark-minimal has its own harness in
+/// `benches/coremark.rs`.
+pub const WASMI_EXECUTABLE: &[(&str, &str, &[i64])] = &[
+    // m_cost = 1 MiB
+    ("argon2", "run", &[1]),
+    ("bz2", "_start", &[]),
+    ("erc20", "deploy", &[]),
+    ("erc20", "call", &[]),
+    ("pulldown-cmark", "_start", &[]),
+];
+
+/// Input `seal_input` hands to the `deploy` / `call` entry of erc20
+///
+/// `deploy` runs the `new(1_000_000)` constructor and `call` transfers 1 to
+/// another account, each input being the selector of the constructor or
+/// message followed by its SCALE-encoded arguments. `call` expects a contract
+/// deployed in the same store.
+pub fn erc20_input(func_name: &str) -> Vec<u8> {
+    // blake2b-256 of "new" and "transfer", truncated to 4 bytes
+    const NEW: [u8; 4] = [0x9b, 0xae, 0x9d, 0x5e];
+    const TRANSFER: [u8; 4] = [0x84, 0xa1, 0x5d, 0xa1];
+
+    match func_name {
+        "deploy" => [&NEW[..], &1_000_000u128.to_le_bytes()].concat(),
+        _ => [&TRANSFER[..], &[2; 32], &1u128.to_le_bytes()].concat(),
+    }
+}
+
+/// Directory holding the `default.input*` files read by the sightglass-style
+/// wasmi-benchmarks modules (bz2, pulldown-cmark)
+///
+/// The inputs are synthesized under the system temp directory, so every
+/// engine version reads the same bytes.
+pub fn wasmi_input_dir() -> std::io::Result<PathBuf> {
+    let dir = std::env::temp_dir().join("benchmark-wasmtime-wasmi");
+    std::fs::create_dir_all(&dir)?;
+
+    let markdown = "# Hello world\n\n\
+        This is a ~~complicated~~ *very simple* example with `code` and a [link](https://example.com).\n\n\
+        - [x] done\n- [ ] todo\n\n\
+        | a | b |\n|---|---|\n| 1 | 2 |\n\n\
+        ```rust\nfn main() {}\n```\n\n"
+        .repeat(1024);
+    std::fs::write(dir.join("default.input"), &markdown)?;
+    std::fs::write(dir.join("default.input.md"), &markdown)?;
+
+    Ok(dir)
+}
+
+/// Run `coremark-minimal` once with runtime `R` and return its score
+///
+/// The guest measures itself through the `env.clock_ms` host import and
+/// returns the CoreMark score, i.e. iterations per second.
+pub fn coremark_score<R: Runtime>(
+    engine: &R::Engine,
+    pre_instance: &R::CoreInstancePre,
+) -> Result<f64> {
+    let mut store = R::new_core_store(engine, None)?;
+    let instance = R::instantiate_core(pre_instance, &mut store)?;
+
+    let results = R::call_core(&instance, &mut store, "run", &[]).context("run coremark")?;
+    results
+        .first()
+        .and_then(R::core_val_as_f64)
+        .context("coremark returns no score")
+}
+
+/// Compile `coremark-minimal` with runtime `R` and pre-instantiate it
+pub fn setup_coremark<R: Runtime>() -> Result<(R::Engine, R::CoreInstancePre)> {
+    let engine = R::new_engine(&EngineOptions::default())?;
+    let module = R::load_module(&engine, testdata::wasmi::COREMARK_MINIMAL)?;
+    let pre_instance = R::core_instance_pre(&engine, &module)?;
+
+    Ok((engine, pre_instance))
+}
+
+/// Directory where non-criterion results (e.g. CoreMark scores) are written,
+/// next to `target/criterion`
+pub fn get_report_dir(name: &str) -> PathBuf {
+    let target_dir = std::env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../target"));
+    target_dir.join(name)
+}
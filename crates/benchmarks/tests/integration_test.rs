@@ -1,10 +1,18 @@
 use anyhow::{Context, Result};
+use benchmarks::{
+    GOLDEN_COMPONENTS, erc20_input, for_each_runtime, get_golden_wasm_path, golden_functions,
+    golden_pooling_limits,
+};
+use engine::{
+    Allocator, EngineOptions, EpochTicker, Runtime, Timeout, TypedRuntime, Value, WasiOptions,
+};
+use std::time::{Duration, Instant};
 use testdata::{load_fixture, validate_wasm};
 
-use engine::v21::{Config, Engine as EngineV21, Module as ModuleV21};
-use engine::v21::{execute as execute_v21, version as version_v21};
-use engine::v41::{Config as ConfigV41, Engine as EngineV41, Module as ModuleV41};
-use engine::v41::{execute as execute_v41, version as version_v41};
+use engine::v21::{Config, Engine as EngineV21, Module as ModuleV21, Val as ValV21};
+use engine::v21::{V21, execute as execute_v21, version as version_v21};
+use engine::v41::{Config as ConfigV41, Engine as EngineV41, Module as ModuleV41, Val as ValV41};
+use engine::v41::{V41, execute as execute_v41, version as version_v41};
 
 #[test]
 fn test_wasmtime_v21_execute_simple() -> Result<()> {
@@ -18,7 +26,8 @@ fn test_wasmtime_v21_execute_simple() -> Result<()> {
     let result = execute_v21(&engine, &module, "add", &[]).context("执行函数失败")?;
 
     // The add function returns 100 (42 + 58)
-    assert_eq!(result, 100i32.to_le_bytes().to_vec());
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].i32(), Some(100));
     Ok(())
 }
 
@@ -34,7 +43,8 @@ fn test_wasmtime_v41_execute_simple() -> Result<()> {
     let result = execute_v41(&engine, &module, "add", &[]).context("执行函数失败")?;
 
     // The add function returns 100 (42 + 58)
-    assert_eq!(result, 100i32.to_le_bytes().to_vec());
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].i32(), Some(100));
     Ok(())
 }
 
@@ -50,7 +60,8 @@ fn test_wasmtime_v21_execute_complex() -> Result<()> {
     let result = execute_v21(&engine, &module, "fibonacci", &[]).context("执行函数失败")?;
 
     // The fibonacci function returns fib(11) = 89
-    assert_eq!(result, 89i32.to_le_bytes().to_vec());
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].i32(), Some(89));
     Ok(())
 }
 
@@ -66,7 +77,82 @@ fn test_wasmtime_v41_execute_complex() -> Result<()> {
     let result = execute_v41(&engine, &module, "fibonacci", &[]).context("执行函数失败")?;
 
     // The fibonacci function returns fib(11) = 89
-    assert_eq!(result, 89i32.to_le_bytes().to_vec());
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].i32(), Some(89));
+    Ok(())
+}
+
+/// Run the exports of the "params" fixture, which take parameters and
+/// return multiple values, with runtime `R`
+fn execute_params_fixture<R: Runtime>() -> Result<()>
+where
+    R::CoreVal: std::fmt::Debug,
+{
+    let wasm_bytes = load_fixture("params");
+    validate_wasm(&wasm_bytes)?;
+
+    let engine = R::new_engine(&EngineOptions::default())?;
+    let module = R::load_module(&engine, &wasm_bytes)?;
+
+    let result = R::execute(
+        &engine,
+        &module,
+        "add",
+        &[R::CoreVal::from(40i32), R::CoreVal::from(2i32)],
+    )
+    .context("执行函数失败")?;
+    assert_eq!(result.len(), 1);
+
+    let result = R::execute(
+        &engine,
+        &module,
+        "swap",
+        &[R::CoreVal::from(7i64), R::CoreVal::from(0.5f64)],
+    )
+    .context("执行函数失败")?;
+    assert_eq!(result.len(), 2);
+
+    // Wrong arity and wrong types are rejected before the call
+    let err = R::execute(&engine, &module, "add", &[R::CoreVal::from(1i32)]).unwrap_err();
+    assert!(format!("{err:#}").contains("签名不匹配"), "{err:#}");
+
+    let err = R::execute(
+        &engine,
+        &module,
+        "add",
+        &[R::CoreVal::from(1i64), R::CoreVal::from(2i32)],
+    )
+    .unwrap_err();
+    assert!(format!("{err:#}").contains("签名不匹配"), "{err:#}");
+
+    Ok(())
+}
+
+#[test]
+fn test_wasmtime_v21_execute_params() -> Result<()> {
+    execute_params_fixture::<V21>()?;
+
+    let engine = EngineV21::new(&Config::new())?;
+    let module = ModuleV21::from_binary(&engine, &load_fixture("params"))?;
+    let result = execute_v21(
+        &engine,
+        &module,
+        "swap",
+        &[ValV21::I64(7), ValV21::from(0.5f64)],
+    )?;
+    assert_eq!(result[0].f64(), Some(0.5));
+    assert_eq!(result[1].i64(), Some(7));
+    Ok(())
+}
+
+#[test]
+fn test_wasmtime_v41_execute_params() -> Result<()> {
+    execute_params_fixture::<V41>()?;
+
+    let engine = EngineV41::new(&ConfigV41::new())?;
+    let module = ModuleV41::from_binary(&engine, &load_fixture("params"))?;
+    let result = execute_v41(&engine, &module, "add", &[ValV41::I32(40), ValV41::I32(2)])?;
+    assert_eq!(result[0].i32(), Some(42));
     Ok(())
 }
 
@@ -78,3 +164,338 @@ fn test_version_consistency() -> Result<()> {
     assert_ne!(version_v21(), version_v41());
     Ok(())
 }
+
+/// Instantiate pulldown-cmark.wasm and call its parse function through `Runtime`
+fn call_pulldown_cmark_parse<R: Runtime>() -> Result<()> {
+    let options = EngineOptions::default();
+    let engine = R::new_engine(&options)?;
+    let component = R::load_component(&engine, &get_golden_wasm_path("pulldown-cmark.wasm"))?;
+    let pre_instance = R::instantiate_pre(&engine, &component, &options)?;
+
+    let mut store = R::new_store(&engine);
+    let instance = R::instantiate(&pre_instance, &mut store)?;
+    let func = R::find_func(
+        &instance,
+        &mut store,
+        "sammyne:pulldown-cmark/api@1.0.0#parse",
+    )?;
+
+    let params = [R::Val::from(Value::from("*hello*"))];
+    let mut results = vec![R::Val::from(Value::Bool(false)); R::results_len(&func, &store)];
+    assert_eq!(results.len(), 1);
+
+    R::call(&func, &mut store, &params, &mut results).context("call parse")?;
+    R::post_return(&func, &mut store).context("post-return")?;
+
+    Ok(())
+}
+
+#[test]
+fn test_runtime_v21_call_component() -> Result<()> {
+    call_pulldown_cmark_parse::<V21>()
+}
+
+#[test]
+fn test_runtime_v41_call_component() -> Result<()> {
+    call_pulldown_cmark_parse::<V41>()
+}
+
+/// Call pulldown-cmark.wasm parse through its `bindgen!` binding
+fn call_pulldown_cmark_typed<R: TypedRuntime>() -> Result<()> {
+    let options = EngineOptions::default();
+    let engine = R::new_engine(&options)?;
+    let component = R::load_component(&engine, &get_golden_wasm_path("pulldown-cmark.wasm"))?;
+    let pre_instance = R::instantiate_pre(&engine, &component, &options)?;
+
+    let mut store = R::new_store(&engine);
+    let instance = R::instantiate(&pre_instance, &mut store)?;
+    let world = R::pulldown_cmark(&instance, &mut store)?;
+
+    // the binding runs post-return itself, so calling twice must work
+    for _ in 0..2 {
+        let html = R::pulldown_cmark_parse(&world, &mut store, "*hello*")?;
+        assert_eq!(html.as_deref(), Ok("<p><em>hello</em></p>\n"));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_runtime_v21_call_typed() -> Result<()> {
+    call_pulldown_cmark_typed::<V21>()
+}
+
+#[test]
+fn test_runtime_v41_call_typed() -> Result<()> {
+    call_pulldown_cmark_typed::<V41>()
+}
+
+#[test]
+fn test_enabled_runtimes_call_component() -> Result<()> {
+    let mut versions = vec![];
+    for_each_runtime!(R => {
+        call_pulldown_cmark_parse::<R>().context(R::version())?;
+        versions.push(R::version());
+    });
+
+    // v21 and v41 are always enabled, the rest come in ascending order
+    assert_eq!(versions.first(), Some(&"wasmtime-21.0"));
+    assert_eq!(versions.last(), Some(&"wasmtime-41.0"));
+    assert!(versions.is_sorted());
+    Ok(())
+}
+
+/// Deploy erc20 and transfer tokens with it, then run argon2 from
+/// wasmi-benchmarks
+fn run_wasmi_modules<R: Runtime>() -> Result<()> {
+    let engine = R::new_engine(&EngineOptions::default())?;
+
+    let erc20 = engine::contract::export_memory(testdata::wasmi::ERC20)?;
+    let erc20 = R::load_module(&engine, &erc20)?;
+    let pre_instance = R::core_instance_pre(&engine, &erc20)?;
+    let mut store = R::new_core_store(&engine, None)?;
+    let instance = R::instantiate_core(&pre_instance, &mut store).context("instantiate erc20")?;
+    for func_name in ["deploy", "call"] {
+        R::set_contract_input(&mut store, erc20_input(func_name));
+        R::call_core(&instance, &mut store, func_name, &[]).context(func_name)?;
+    }
+
+    let argon2 = R::load_module(&engine, testdata::wasmi::ARGON2)?;
+    let pre_instance = R::core_instance_pre(&engine, &argon2)?;
+    let mut store = R::new_core_store(&engine, None)?;
+    let instance = R::instantiate_core(&pre_instance, &mut store)?;
+    let result = R::call_core(&instance, &mut store, "run", &[R::CoreVal::from(1i64)])
+        .context("run argon2")?;
+    assert_eq!(result.len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_wasmtime_v21_wasmi_modules() -> Result<()> {
+    run_wasmi_modules::<V21>()
+}
+
+#[test]
+fn test_wasmtime_v41_wasmi_modules() -> Result<()> {
+    run_wasmi_modules::<V41>()
+}
+
+/// Precompile pulldown-cmark.wasm and make sure the artifact round-trips
+fn precompile_round_trip<R: Runtime>() -> Result<()> {
+    let options = EngineOptions::default();
+    let engine = R::new_engine(&options)?;
+
+    let wasm_bytes = std::fs::read(get_golden_wasm_path("pulldown-cmark.wasm"))?;
+    let artifact = R::precompile_component(&engine, &wasm_bytes)?;
+
+    // SAFETY: the artifact was just produced by the same engine
+    let component = unsafe { R::deserialize_component(&engine, &artifact)? };
+    let pre_instance = R::instantiate_pre(&engine, &component, &options)?;
+    let mut store = R::new_store(&engine);
+    R::instantiate(&pre_instance, &mut store).context("instantiate deserialized component")?;
+
+    let serialized = R::serialize_component(&component)?;
+    // SAFETY: as above
+    unsafe { R::deserialize_component(&engine, &serialized)? };
+
+    Ok(())
+}
+
+#[test]
+fn test_wasmtime_v21_precompile() -> Result<()> {
+    precompile_round_trip::<V21>()
+}
+
+#[test]
+fn test_wasmtime_v41_precompile() -> Result<()> {
+    precompile_round_trip::<V41>()
+}
+
+/// Instantiate every golden component twice with the pooling allocator
+fn instantiate_pooling<R: Runtime>() -> Result<()> {
+    for wasm_file in GOLDEN_COMPONENTS {
+        let options = EngineOptions {
+            allocator: Allocator::Pooling(golden_pooling_limits(wasm_file)),
+            ..Default::default()
+        };
+        let engine = R::new_engine(&options)?;
+        let component = R::load_component(&engine, &get_golden_wasm_path(wasm_file))?;
+        let pre_instance = R::instantiate_pre(&engine, &component, &options)?;
+
+        // the second round reuses the slots released by the first store
+        for _ in 0..2 {
+            let mut store = R::new_store(&engine);
+            R::instantiate(&pre_instance, &mut store)
+                .with_context(|| format!("instantiate {wasm_file} with pooling"))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_wasmtime_v21_pooling() -> Result<()> {
+    instantiate_pooling::<V21>()
+}
+
+#[test]
+fn test_wasmtime_v41_pooling() -> Result<()> {
+    instantiate_pooling::<V41>()
+}
+
+/// Call every golden function in a store capturing its output, returning
+/// what the guests wrote to stdout and stderr
+fn capture_golden_output<R: Runtime>() -> Result<(Vec<u8>, Vec<u8>)> {
+    let options = EngineOptions::default();
+    let engine = R::new_engine(&options)?;
+    let wasi_options = WasiOptions::new().capture_output();
+
+    let (mut stdout, mut stderr) = (vec![], vec![]);
+    for f in golden_functions() {
+        let component = R::load_component(&engine, &get_golden_wasm_path(f.wasm_file))?;
+        let pre_instance = R::instantiate_pre(&engine, &component, &options)?;
+
+        let mut store = R::new_store_with(&engine, &wasi_options)?;
+        let instance = R::instantiate(&pre_instance, &mut store)?;
+        let func = R::find_func(&instance, &mut store, f.name)?;
+
+        let params: Vec<R::Val> = f.params.into_iter().map(R::Val::from).collect();
+        let mut results = vec![R::Val::from(Value::Bool(false)); R::results_len(&func, &store)];
+        R::call(&func, &mut store, &params, &mut results).with_context(|| f.name.to_string())?;
+        R::post_return(&func, &mut store)?;
+
+        stdout.extend(R::captured_stdout(&store).context("stdout not captured")?);
+        stderr.extend(R::captured_stderr(&store).context("stderr not captured")?);
+    }
+
+    // nothing is captured unless asked for
+    assert_eq!(R::captured_stdout(&R::new_store(&engine)), None);
+
+    Ok((stdout, stderr))
+}
+
+#[test]
+fn test_captured_output_consistency() -> Result<()> {
+    assert_eq!(
+        capture_golden_output::<V21>()?,
+        capture_golden_output::<V41>()?
+    );
+    Ok(())
+}
+
+/// Hash with argon2.wasm in deterministic mode, twice in separate stores
+fn deterministic_argon2_hash<R: TypedRuntime>() -> Result<Vec<Result<Vec<u8>, String>>> {
+    let options = EngineOptions {
+        deterministic: true,
+        ..Default::default()
+    };
+    let engine = R::new_engine(&options)?;
+    let component = R::load_component(&engine, &get_golden_wasm_path("argon2.wasm"))?;
+    let pre_instance = R::instantiate_pre(&engine, &component, &options)?;
+
+    let mut hashes = vec![];
+    for _ in 0..2 {
+        let mut store = R::new_store_with(&engine, &WasiOptions::new().deterministic())?;
+        let instance = R::instantiate(&pre_instance, &mut store)?;
+        let world = R::argon2(&instance, &mut store)?;
+        hashes.push(R::argon2_hash(
+            &world,
+            &mut store,
+            b"password",
+            "somesaltsomesalt",
+        )?);
+    }
+
+    Ok(hashes)
+}
+
+#[test]
+fn test_deterministic_mode_consistency() -> Result<()> {
+    let hashes = deterministic_argon2_hash::<V21>()?;
+    assert!(hashes[0].is_ok());
+    assert_eq!(hashes[0], hashes[1]);
+    assert_eq!(hashes, deterministic_argon2_hash::<V41>()?);
+    Ok(())
+}
+
+/// Component exporting `api#spin`, which never returns
+const SPIN_COMPONENT: &str = r#"
+(component
+    (core module $m (func (export "spin") (loop $l (br $l))))
+    (core instance $i (instantiate $m))
+    (func $spin (canon lift (core func $i "spin")))
+    (instance $api (export "spin" (func $spin)))
+    (export "api" (instance $api))
+)
+"#;
+
+/// Interrupt a runaway guest with a timeout
+fn call_with_timeout<R: Runtime + 'static>() -> Result<()> {
+    let options = EngineOptions {
+        epoch_interruption: true,
+        ..Default::default()
+    };
+    let engine = R::new_engine(&options)?;
+    let component = R::compile_component(&engine, SPIN_COMPONENT.as_bytes())?;
+    let pre_instance = R::instantiate_pre(&engine, &component, &options)?;
+    let _ticker = EpochTicker::start::<R>(&engine);
+
+    let mut store = R::new_store(&engine);
+    let instance = R::instantiate(&pre_instance, &mut store)?;
+    let func = R::find_func(&instance, &mut store, "api#spin")?;
+
+    R::set_timeout(&mut store, Duration::from_millis(100));
+    let start = Instant::now();
+    let error = R::call(&func, &mut store, &[], &mut []).expect_err("spin must time out");
+    assert!(error.is::<Timeout>(), "{error:?}");
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    Ok(())
+}
+
+#[test]
+fn test_wasmtime_v21_timeout() -> Result<()> {
+    call_with_timeout::<V21>()
+}
+
+#[test]
+fn test_wasmtime_v41_timeout() -> Result<()> {
+    call_with_timeout::<V41>()
+}
+
+/// Hash with argon2.wasm, whose default parameters take 19 MiB of memory
+/// (`m_cost` of 19456 KiB), in a store whose linear memory is limited to
+/// `max_memory` bytes
+fn argon2_hash_limited<R: TypedRuntime>(max_memory: usize) -> Result<Result<Vec<u8>, String>> {
+    let options = EngineOptions::default();
+    let engine = R::new_engine(&options)?;
+    let component = R::load_component(&engine, &get_golden_wasm_path("argon2.wasm"))?;
+    let pre_instance = R::instantiate_pre(&engine, &component, &options)?;
+
+    let mut store = R::new_store_with(&engine, &WasiOptions::new().max_memory(max_memory))?;
+    let instance = R::instantiate(&pre_instance, &mut store)?;
+    let world = R::argon2(&instance, &mut store)?;
+    R::argon2_hash(&world, &mut store, b"password", "somesaltsomesalt")
+}
+
+/// The default argon2 parameters fail below their 19 MiB cost and succeed
+/// above it
+fn store_limits<R: TypedRuntime>() -> Result<()> {
+    let error = argon2_hash_limited::<R>(16 << 20).expect_err("argon2 must exceed 16 MiB");
+    assert!(format!("{error:?}").contains("memory"), "{error:?}");
+
+    let hash = argon2_hash_limited::<R>(128 << 20)?;
+    assert_eq!(hash.map(|h| h.len()), Ok(32));
+    Ok(())
+}
+
+#[test]
+fn test_wasmtime_v21_store_limits() -> Result<()> {
+    store_limits::<V21>()
+}
+
+#[test]
+fn test_wasmtime_v41_store_limits() -> Result<()> {
+    store_limits::<V41>()
+}
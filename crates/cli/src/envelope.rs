@@ -0,0 +1,170 @@
+use serde_json::{Value, json};
+use std::fmt;
+use std::time::Duration;
+
+/// Category of a failed invocation, reported as `kind` in the error envelope
+/// and as the exit code of the process
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The WASM file or the function doesn't exist
+    NotFound,
+    /// A JSON parameter or the WASM file can't be parsed
+    Parse,
+    /// WASI or the imports of the component can't be linked
+    Link,
+    /// The component fails to instantiate
+    Instantiate,
+    /// The call traps
+    Trap,
+    /// The parameters don't match the signature of the function
+    TypeMismatch,
+    /// Instantiation or the call is interrupted by `--timeout`
+    Timeout,
+    /// The engine can't be created with the given options
+    Engine,
+    /// The call fails in the host, e.g. a memory grows beyond `--max-memory`
+    Host,
+}
+
+impl ErrorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorKind::NotFound => "not-found",
+            ErrorKind::Parse => "parse",
+            ErrorKind::Link => "link",
+            ErrorKind::Instantiate => "instantiate",
+            ErrorKind::Trap => "trap",
+            ErrorKind::TypeMismatch => "type-mismatch",
+            ErrorKind::Timeout => "timeout",
+            ErrorKind::Engine => "engine",
+            ErrorKind::Host => "host",
+        }
+    }
+
+    /// Exit code of the process, stable across releases
+    ///
+    /// 1 is left to errors outside of the envelope and 2 to usage errors
+    /// reported by clap.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            ErrorKind::NotFound => 3,
+            ErrorKind::Parse => 4,
+            ErrorKind::Link => 5,
+            ErrorKind::Instantiate => 6,
+            ErrorKind::Trap => 7,
+            ErrorKind::TypeMismatch => 8,
+            ErrorKind::Timeout => 9,
+            ErrorKind::Engine => 10,
+            ErrorKind::Host => 11,
+        }
+    }
+}
+
+/// An error of an invocation along with its category
+#[derive(Debug)]
+pub struct Failure {
+    pub kind: ErrorKind,
+    pub error: anyhow::Error,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.error)
+    }
+}
+
+impl Failure {
+    /// The error envelope, e.g.
+    /// `{"success": false, "kind": "not-found", "message": ..., "context": [...]}`
+    ///
+    /// `message` is the outermost error and `context` the chain of its
+    /// causes, the root cause last.
+    pub fn to_json(&self) -> Value {
+        let context: Vec<String> = self.error.chain().skip(1).map(|e| e.to_string()).collect();
+        json!({
+            "success": false,
+            "kind": self.kind.as_str(),
+            "message": self.error.to_string(),
+            "context": context,
+        })
+    }
+}
+
+/// Attach an [`ErrorKind`] to the error of a result
+pub trait ResultExt<T> {
+    fn kind(self, kind: ErrorKind) -> Result<T, Failure>;
+}
+
+impl<T> ResultExt<T> for anyhow::Result<T> {
+    fn kind(self, kind: ErrorKind) -> Result<T, Failure> {
+        self.map_err(|error| Failure { kind, error })
+    }
+}
+
+/// Time spent in each phase of a successful invocation
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Timing {
+    /// Creating the engine and compiling the component
+    pub load: Duration,
+    /// Linking WASI and instantiating the component
+    pub instantiate: Duration,
+    pub call: Duration,
+}
+
+impl Timing {
+    pub fn to_json(&self) -> Value {
+        let us = |d: Duration| d.as_micros() as u64;
+        json!({
+            "load_us": us(self.load),
+            "instantiate_us": us(self.instantiate),
+            "call_us": us(self.call),
+            "total_us": us(self.load + self.instantiate + self.call),
+        })
+    }
+}
+
+/// The success envelope, e.g.
+/// `{"success": true, "result": ..., "timing": {...}}`, with the fuel
+/// consumed when metered
+pub fn success_json(result: Value, timing: &Timing, fuel_consumed: Option<u64>) -> Value {
+    let mut envelope = json!({
+        "success": true,
+        "result": result,
+        "timing": timing.to_json(),
+    });
+    if let Some(fuel) = fuel_consumed {
+        envelope["fuel"] = json!(fuel);
+    }
+    envelope
+}
+
+/// The envelope of `--engine all`, e.g.
+/// `{"success": true, "differ": false, "engines": {"v21": {...}, "v41": {...}}}`
+///
+/// `engines` holds the envelope of the run on each engine. The runs differ
+/// when one succeeds and another fails, when their results aren't equal or
+/// when they fail with different kinds.
+pub fn comparison_json(runs: &[(&str, Result<Value, Failure>)]) -> Value {
+    let outcome = |run: &Result<Value, Failure>| match run {
+        Ok(envelope) => json!({ "result": envelope["result"] }),
+        Err(failure) => json!({ "kind": failure.kind.as_str() }),
+    };
+    let differ = runs
+        .windows(2)
+        .any(|pair| outcome(&pair[0].1) != outcome(&pair[1].1));
+
+    let mut engines = serde_json::Map::with_capacity(runs.len());
+    for (name, run) in runs {
+        let envelope = match run {
+            Ok(envelope) => envelope.clone(),
+            Err(failure) => failure.to_json(),
+        };
+        engines.insert(name.to_string(), envelope);
+    }
+
+    json!({
+        "success": runs.iter().all(|(_, run)| run.is_ok()),
+        "differ": differ,
+        "engines": engines,
+    })
+}
@@ -0,0 +1,199 @@
+//! `wasmtime-cli inspect`: the exports and imports of a component, with
+//! WIT-like signatures
+//!
+//! Items of an instance are flattened as `interface#item`, the way `-f`
+//! expects function names, e.g.
+//! `sammyne:argon2/api@1.0.0#hash: func(password: list<u8>, salt: string) -> result<list<u8>, string>`.
+//!
+//! wasmtime doesn't expose the names of resources and of named types, so
+//! handles are written `own<_>` / `borrow<_>` and types are written inline.
+
+use crate::envelope::Failure;
+use crate::load_component;
+use engine::v41::Engine;
+use engine::v41::V41;
+use engine::v41::component::types::{ComponentFunc, ComponentItem};
+use engine::{EngineOptions, ValueType};
+use serde_json::{Value, json};
+use std::fmt;
+use std::path::PathBuf;
+
+/// An export or an import of a component
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Item {
+    /// Name of the item, `interface#item` for items of an instance
+    pub name: String,
+    /// `func`, `resource`, `type`, `instance`, `component`, `module` or
+    /// `core-func`
+    pub kind: &'static str,
+    /// WIT-like signature of the item, e.g. `func(a: u32) -> string`
+    pub signature: String,
+}
+
+/// The exports and imports of a component
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Inspection {
+    pub exports: Vec<Item>,
+    pub imports: Vec<Item>,
+}
+
+impl Inspection {
+    /// The inspection envelope, e.g.
+    /// `{"success": true, "exports": [{"name": ..., "kind": "func", "signature": ...}], "imports": [...]}`
+    pub fn to_json(&self) -> Value {
+        let items = |items: &[Item]| -> Vec<Value> {
+            items
+                .iter()
+                .map(|item| {
+                    json!({
+                        "name": item.name,
+                        "kind": item.kind,
+                        "signature": item.signature,
+                    })
+                })
+                .collect()
+        };
+        json!({
+            "success": true,
+            "exports": items(&self.exports),
+            "imports": items(&self.imports),
+        })
+    }
+}
+
+impl fmt::Display for Inspection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (title, items) in [("exports", &self.exports), ("imports", &self.imports)] {
+            writeln!(f, "{title}:")?;
+            for item in items {
+                writeln!(f, "  {}: {}", item.name, item.signature)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Inspect the exports and imports of a WASM Component file
+///
+/// # Returns
+/// The inspection, or the `not-found` / `parse` failure of loading the file
+pub fn inspect(path: &PathBuf) -> Result<Inspection, Failure> {
+    let (component, engine) = load_component::<V41>(path, &EngineOptions::default())?;
+    let ty = component.component_type();
+
+    let mut exports = Vec::new();
+    for (name, item) in ty.exports(&engine) {
+        collect(&engine, name.to_string(), item, &mut exports);
+    }
+    let mut imports = Vec::new();
+    for (name, item) in ty.imports(&engine) {
+        collect(&engine, name.to_string(), item, &mut imports);
+    }
+
+    Ok(Inspection { exports, imports })
+}
+
+/// Push `item` to `items`, flattening the items of an instance
+fn collect(engine: &Engine, name: String, item: ComponentItem, items: &mut Vec<Item>) {
+    let (kind, signature) = match item {
+        ComponentItem::ComponentFunc(func) => ("func", func_signature(&func)),
+        ComponentItem::Resource(_) => ("resource", "resource".to_string()),
+        ComponentItem::Type(ty) => ("type", wit(&ValueType::from(ty))),
+        ComponentItem::ComponentInstance(instance) => {
+            let len = items.len();
+            for (export, item) in instance.exports(engine) {
+                collect(engine, format!("{name}#{export}"), item, items);
+            }
+            if items.len() > len {
+                return;
+            }
+            // Keep an empty instance visible
+            ("instance", "instance".to_string())
+        }
+        ComponentItem::Component(_) => ("component", "component".to_string()),
+        ComponentItem::Module(_) => ("module", "module".to_string()),
+        ComponentItem::CoreFunc(_) => ("core-func", "core-func".to_string()),
+    };
+    items.push(Item {
+        name,
+        kind,
+        signature,
+    });
+}
+
+/// WIT-like signature of a function, e.g. `func(a: u32, b: u32) -> u32`
+fn func_signature(func: &ComponentFunc) -> String {
+    let params = func
+        .params()
+        .map(|(name, ty)| format!("{name}: {}", wit(&ValueType::from(ty))))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let results = func
+        .results()
+        .map(|ty| wit(&ValueType::from(ty)))
+        .collect::<Vec<_>>();
+    let prefix = if func.async_() { "async func" } else { "func" };
+    match results.as_slice() {
+        [] => format!("{prefix}({params})"),
+        [result] => format!("{prefix}({params}) -> {result}"),
+        results => format!("{prefix}({params}) -> ({})", results.join(", ")),
+    }
+}
+
+/// WIT syntax of a type, anonymous types being written inline, e.g.
+/// `record { a: u32, b: option<string> }`
+pub fn wit(ty: &ValueType) -> String {
+    let join = |types: &[ValueType]| types.iter().map(wit).collect::<Vec<_>>().join(", ");
+    let optional = |name: &str, ty: &Option<Box<ValueType>>| match ty {
+        Some(ty) => format!("{name}<{}>", wit(ty)),
+        None => name.to_string(),
+    };
+    match ty {
+        ValueType::Bool => "bool".to_string(),
+        ValueType::S8 => "s8".to_string(),
+        ValueType::U8 => "u8".to_string(),
+        ValueType::S16 => "s16".to_string(),
+        ValueType::U16 => "u16".to_string(),
+        ValueType::S32 => "s32".to_string(),
+        ValueType::U32 => "u32".to_string(),
+        ValueType::S64 => "s64".to_string(),
+        ValueType::U64 => "u64".to_string(),
+        ValueType::Float32 => "f32".to_string(),
+        ValueType::Float64 => "f64".to_string(),
+        ValueType::Char => "char".to_string(),
+        ValueType::String => "string".to_string(),
+        ValueType::List(ty) => format!("list<{}>", wit(ty)),
+        ValueType::Record(fields) => {
+            let fields = fields
+                .iter()
+                .map(|(name, ty)| format!("{name}: {}", wit(ty)))
+                .collect::<Vec<_>>();
+            format!("record {{ {} }}", fields.join(", "))
+        }
+        ValueType::Tuple(types) => format!("tuple<{}>", join(types)),
+        ValueType::Variant(cases) => {
+            let cases = cases
+                .iter()
+                .map(|(name, ty)| match ty {
+                    Some(ty) => format!("{name}({})", wit(ty)),
+                    None => name.clone(),
+                })
+                .collect::<Vec<_>>();
+            format!("variant {{ {} }}", cases.join(", "))
+        }
+        ValueType::Enum(names) => format!("enum {{ {} }}", names.join(", ")),
+        ValueType::Option(ty) => format!("option<{}>", wit(ty)),
+        ValueType::Result { ok, err } => match (ok, err) {
+            (None, None) => "result".to_string(),
+            (Some(ok), None) => format!("result<{}>", wit(ok)),
+            (None, Some(err)) => format!("result<_, {}>", wit(err)),
+            (Some(ok), Some(err)) => format!("result<{}, {}>", wit(ok), wit(err)),
+        },
+        ValueType::Flags(names) => format!("flags {{ {} }}", names.join(", ")),
+        ValueType::Own => "own<_>".to_string(),
+        ValueType::Borrow => "borrow<_>".to_string(),
+        ValueType::Future(ty) => optional("future", ty),
+        ValueType::Stream(ty) => optional("stream", ty),
+        ValueType::ErrorContext => "error-context".to_string(),
+    }
+}
@@ -0,0 +1,405 @@
+//! JSON encoding of component-model values
+//!
+//! Parameters are decoded against the type the function expects, results are
+//! encoded against the type it returns, so the output of a call can be fed
+//! back as a parameter:
+//!
+//! | Type | JSON | Example |
+//! |------|------|---------|
+//! | `bool` | boolean | `true` |
+//! | `s8` … `u64` | integer within the range of the type | `42` |
+//! | `float32`, `float64` | number, or `"NaN"`, `"Infinity"` and `"-Infinity"` | `3.14` |
+//! | `char` | string of a single Unicode scalar value | `"🦀"` |
+//! | `string` | string | `"hello"` |
+//! | `list<T>` | array | `[1, 2, 3]` |
+//! | `record` | object keyed by field name, every field required | `{"name": "x", "size": 1}` |
+//! | `tuple<T...>` | array of exactly the arity of the tuple | `[1, "a"]` |
+//! | `variant` | object with a single key, the case, whose value is the payload or `null` | `{"circle": 1.5}`, `{"empty": null}` |
+//! | `enum` | string naming the case | `"red"` |
+//! | `option<T>` | `null` for `none`, the value itself for `some` | `null`, `7` |
+//! | `option<option<T>>` | `null` for `none`, `{"some": ...}` for `some` | `{"some": null}` |
+//! | `result<T, E>` | `{"ok": ...}` or `{"err": ...}`, `null` when the case has no payload | `{"ok": 1}`, `{"err": null}` |
+//! | `flags` | array of the names of the flags set | `["read", "write"]` |
+//!
+//! A case of a variant without payload may also be given as a bare string,
+//! e.g. `"empty"`, but is always encoded as an object.
+//!
+//! Resource handles (`own`, `borrow`) and the async types (`future`,
+//! `stream`, `error-context`) have no JSON encoding: they only exist within a
+//! store, so functions taking or returning them can't be called from the
+//! command line.
+
+use anyhow::{Context, Result, anyhow, bail, ensure};
+use engine::{Value, ValueType};
+use serde_json::{Map, Value as Json, json};
+
+/// Convert JSON value to WASM value based on expected type
+///
+/// # Arguments
+/// * `value` - JSON value to convert
+/// * `expect_type` - Expected WASM type for the conversion
+///
+/// # Returns
+/// The converted version-neutral value
+pub fn json_to_wasm_value(value: Json, expect_type: &ValueType) -> Result<Value> {
+    match (expect_type, value) {
+        (ValueType::Bool, Json::Bool(v)) => Ok(Value::Bool(v)),
+        (ValueType::S8, Json::Number(n)) => n
+            .as_i64()
+            .and_then(|i| i.try_into().ok())
+            .map(Value::S8)
+            .ok_or_else(|| anyhow!("Expected s8 integer")),
+        (ValueType::S16, Json::Number(n)) => n
+            .as_i64()
+            .and_then(|i| i.try_into().ok())
+            .map(Value::S16)
+            .ok_or_else(|| anyhow!("Expected s16 integer")),
+        (ValueType::S32, Json::Number(n)) => n
+            .as_i64()
+            .and_then(|i| i.try_into().ok())
+            .map(Value::S32)
+            .ok_or_else(|| anyhow!("Expected s32 integer")),
+        (ValueType::S64, Json::Number(n)) => n
+            .as_i64()
+            .map(Value::S64)
+            .ok_or_else(|| anyhow!("Expected s64 integer")),
+        (ValueType::U8, Json::Number(n)) => n
+            .as_u64()
+            .and_then(|u| u.try_into().ok())
+            .map(Value::U8)
+            .ok_or_else(|| anyhow!("Expected u8 integer")),
+        (ValueType::U16, Json::Number(n)) => n
+            .as_u64()
+            .and_then(|u| u.try_into().ok())
+            .map(Value::U16)
+            .ok_or_else(|| anyhow!("Expected u16 integer")),
+        (ValueType::U32, Json::Number(n)) => n
+            .as_u64()
+            .and_then(|u| u.try_into().ok())
+            .map(Value::U32)
+            .ok_or_else(|| anyhow!("Expected u32 integer")),
+        (ValueType::U64, Json::Number(n)) => n
+            .as_u64()
+            .map(Value::U64)
+            .ok_or_else(|| anyhow!("Expected u64 integer")),
+        (ValueType::Float32, v) => json_to_float(v).map(|f| Value::Float32(f as f32)),
+        (ValueType::Float64, v) => json_to_float(v).map(Value::Float64),
+        (ValueType::Char, Json::String(s)) => {
+            let mut chars = s.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(Value::Char(c)),
+                _ => bail!("Expected single character string, got {s:?}"),
+            }
+        }
+        (ValueType::String, Json::String(s)) => Ok(Value::String(s)),
+        (ValueType::List(elem_type), Json::Array(arr)) => {
+            // Convert JSON Array to WASM List
+            let mut wasm_values = Vec::with_capacity(arr.len());
+            for (i, elem) in arr.into_iter().enumerate() {
+                let w = json_to_wasm_value(elem, elem_type)
+                    .with_context(|| format!("bad element {i}"))?;
+                wasm_values.push(w);
+            }
+
+            Ok(Value::List(wasm_values))
+        }
+        (ValueType::Record(fields), Json::Object(mut obj)) => {
+            let mut wasm_values = Vec::with_capacity(fields.len());
+
+            for (name, ty) in fields {
+                let v = obj
+                    .remove(name)
+                    .ok_or_else(|| anyhow!("Field {name} not found"))?;
+
+                let w = json_to_wasm_value(v, ty).with_context(|| format!("bad field {name}"))?;
+
+                wasm_values.push((name.clone(), w));
+            }
+
+            if let Some(name) = obj.keys().next() {
+                bail!("Unknown field {name}");
+            }
+
+            Ok(Value::Record(wasm_values))
+        }
+        (ValueType::Tuple(types), Json::Array(arr)) => {
+            ensure!(
+                arr.len() == types.len(),
+                "Expected tuple of {} elements, got {}",
+                types.len(),
+                arr.len()
+            );
+
+            let mut wasm_values = Vec::with_capacity(arr.len());
+            for (i, (elem, ty)) in arr.into_iter().zip(types).enumerate() {
+                let w = json_to_wasm_value(elem, ty).with_context(|| format!("bad element {i}"))?;
+                wasm_values.push(w);
+            }
+
+            Ok(Value::Tuple(wasm_values))
+        }
+        (ValueType::Variant(cases), Json::String(name)) => {
+            let (_, ty) = find_case(cases, &name)?;
+            ensure!(
+                ty.is_none(),
+                "Case {name} has a payload, expected {{\"{name}\": ...}}"
+            );
+
+            Ok(Value::Variant(name, None))
+        }
+        (ValueType::Variant(cases), Json::Object(obj)) => {
+            let (name, payload) = single_entry(obj)?;
+            let (_, ty) = find_case(cases, &name)?;
+            let payload = json_to_payload(payload, ty.as_ref())
+                .with_context(|| format!("bad case {name}"))?;
+
+            Ok(Value::Variant(name, payload))
+        }
+        (ValueType::Enum(names), Json::String(name)) => {
+            ensure!(names.contains(&name), "Unknown case {name}");
+
+            Ok(Value::Enum(name))
+        }
+        (ValueType::Option(_), Json::Null) => Ok(Value::Option(None)),
+        (ValueType::Option(ty), v) => {
+            // `null` is taken by `none`, so the `some` of a nested option is
+            // spelt out
+            let v = if let ValueType::Option(_) = **ty {
+                let obj = match v {
+                    Json::Object(obj) => obj,
+                    got => bail!("Expected {{\"some\": ...}} for a nested option, got {got}"),
+                };
+                let (name, v) = single_entry(obj)?;
+                ensure!(name == "some", "Expected some, got {name}");
+                v
+            } else {
+                v
+            };
+
+            let w = json_to_wasm_value(v, ty).context("bad some")?;
+            Ok(Value::Option(Some(Box::new(w))))
+        }
+        (ValueType::Result { ok, err }, Json::Object(obj)) => {
+            let (name, payload) = single_entry(obj)?;
+            let result = match name.as_str() {
+                "ok" => json_to_payload(payload, ok.as_deref())
+                    .context("bad ok")
+                    .map(Ok)?,
+                "err" => json_to_payload(payload, err.as_deref())
+                    .context("bad err")
+                    .map(Err)?,
+                _ => bail!("Expected ok or err, got {name}"),
+            };
+
+            Ok(Value::Result(result))
+        }
+        (ValueType::Flags(flags), Json::Array(arr)) => {
+            let mut names = Vec::with_capacity(arr.len());
+            for v in arr {
+                let name = match v {
+                    Json::String(name) => name,
+                    got => bail!("Expected flag name, got {got}"),
+                };
+                ensure!(flags.contains(&name), "Unknown flag {name}");
+                names.push(name);
+            }
+
+            Ok(Value::Flags(names))
+        }
+        (
+            ValueType::Own
+            | ValueType::Borrow
+            | ValueType::Future(_)
+            | ValueType::Stream(_)
+            | ValueType::ErrorContext,
+            _,
+        ) => bail!("Resource handles and async values have no JSON encoding"),
+        (expect, got) => bail!("expect {expect:?}, got {got:?}"),
+    }
+}
+
+/// Convert WASM value to JSON value based on its type, the inverse of
+/// [`json_to_wasm_value`]
+///
+/// # Arguments
+/// * `val` - WASM value to convert
+/// * `ty` - Type of the value, e.g. a result type of the function
+///
+/// # Returns
+/// JSON representation of the value
+pub fn wasm_value_to_json(val: &Value, ty: &ValueType) -> Result<Json> {
+    let json = match (ty, val) {
+        (ValueType::Bool, Value::Bool(b)) => Json::Bool(*b),
+        (ValueType::S8, Value::S8(i)) => Json::from(*i),
+        (ValueType::S16, Value::S16(i)) => Json::from(*i),
+        (ValueType::S32, Value::S32(i)) => Json::from(*i),
+        (ValueType::S64, Value::S64(i)) => Json::from(*i),
+        (ValueType::U8, Value::U8(u)) => Json::from(*u),
+        (ValueType::U16, Value::U16(u)) => Json::from(*u),
+        (ValueType::U32, Value::U32(u)) => Json::from(*u),
+        (ValueType::U64, Value::U64(u)) => Json::from(*u),
+        // Widening is exact, so narrowing the decoded f64 gives the f32 back
+        (ValueType::Float32, Value::Float32(f)) => float_to_json(f64::from(*f)),
+        (ValueType::Float64, Value::Float64(f)) => float_to_json(*f),
+        (ValueType::Char, Value::Char(c)) => Json::String(c.to_string()),
+        (ValueType::String, Value::String(s)) => Json::String(s.clone()),
+        (ValueType::List(elem_type), Value::List(items)) => {
+            let items = items
+                .iter()
+                .enumerate()
+                .map(|(i, v)| {
+                    wasm_value_to_json(v, elem_type).with_context(|| format!("bad element {i}"))
+                })
+                .collect::<Result<_>>()?;
+            Json::Array(items)
+        }
+        (ValueType::Record(types), Value::Record(fields)) => {
+            ensure!(
+                types.len() == fields.len(),
+                "Expected record of {} fields, got {}",
+                types.len(),
+                fields.len()
+            );
+
+            let mut obj = Map::with_capacity(fields.len());
+            for ((expect, ty), (name, v)) in types.iter().zip(fields) {
+                ensure!(expect == name, "Expected field {expect}, got {name}");
+                let json =
+                    wasm_value_to_json(v, ty).with_context(|| format!("bad field {name}"))?;
+                obj.insert(name.clone(), json);
+            }
+            Json::Object(obj)
+        }
+        (ValueType::Tuple(types), Value::Tuple(items)) => {
+            ensure!(
+                types.len() == items.len(),
+                "Expected tuple of {} elements, got {}",
+                types.len(),
+                items.len()
+            );
+
+            let items = types
+                .iter()
+                .zip(items)
+                .enumerate()
+                .map(|(i, (ty, v))| {
+                    wasm_value_to_json(v, ty).with_context(|| format!("bad element {i}"))
+                })
+                .collect::<Result<_>>()?;
+            Json::Array(items)
+        }
+        (ValueType::Variant(cases), Value::Variant(name, payload)) => {
+            let (_, ty) = find_case(cases, name)?;
+            let payload = payload_to_json(payload.as_deref(), ty.as_ref())
+                .with_context(|| format!("bad case {name}"))?;
+
+            let mut obj = Map::with_capacity(1);
+            obj.insert(name.clone(), payload);
+            Json::Object(obj)
+        }
+        (ValueType::Enum(names), Value::Enum(name)) => {
+            ensure!(names.contains(name), "Unknown case {name}");
+            Json::String(name.clone())
+        }
+        (ValueType::Option(_), Value::Option(None)) => Json::Null,
+        (ValueType::Option(ty), Value::Option(Some(v))) => {
+            let json = wasm_value_to_json(v, ty).context("bad some")?;
+
+            // `null` is taken by `none`, so the `some` of a nested option is
+            // spelt out
+            if let ValueType::Option(_) = **ty {
+                json!({ "some": json })
+            } else {
+                json
+            }
+        }
+        (ValueType::Result { ok, .. }, Value::Result(Ok(v))) => {
+            let json = payload_to_json(v.as_deref(), ok.as_deref()).context("bad ok")?;
+            json!({ "ok": json })
+        }
+        (ValueType::Result { err, .. }, Value::Result(Err(v))) => {
+            let json = payload_to_json(v.as_deref(), err.as_deref()).context("bad err")?;
+            json!({ "err": json })
+        }
+        (ValueType::Flags(flags), Value::Flags(names)) => {
+            for name in names {
+                ensure!(flags.contains(name), "Unknown flag {name}");
+            }
+            json!(names)
+        }
+        (
+            ValueType::Own
+            | ValueType::Borrow
+            | ValueType::Future(_)
+            | ValueType::Stream(_)
+            | ValueType::ErrorContext,
+            _,
+        ) => bail!("Resource handles and async values have no JSON encoding"),
+        (expect, got) => bail!("expect {expect:?}, got {got:?}"),
+    };
+
+    Ok(json)
+}
+
+/// Encode a float, non-finite values being spelt as strings
+fn float_to_json(f: f64) -> Json {
+    match serde_json::Number::from_f64(f) {
+        Some(n) => Json::Number(n),
+        None if f.is_nan() => Json::from("NaN"),
+        None if f > 0.0 => Json::from("Infinity"),
+        None => Json::from("-Infinity"),
+    }
+}
+
+/// Encode the payload of a case of a variant or result, `null` for none
+fn payload_to_json(val: Option<&Value>, ty: Option<&ValueType>) -> Result<Json> {
+    match (ty, val) {
+        (None, None) => Ok(Json::Null),
+        (Some(ty), Some(v)) => wasm_value_to_json(v, ty),
+        (ty, val) => bail!("Expected payload {ty:?}, got {val:?}"),
+    }
+}
+
+/// Decode a float, non-finite values being spelt as strings
+fn json_to_float(value: Json) -> Result<f64> {
+    match value {
+        Json::Number(n) => n.as_f64().ok_or_else(|| anyhow!("Expected number")),
+        Json::String(s) => match s.as_str() {
+            "NaN" => Ok(f64::NAN),
+            "Infinity" => Ok(f64::INFINITY),
+            "-Infinity" => Ok(f64::NEG_INFINITY),
+            _ => bail!("Expected number, NaN, Infinity or -Infinity, got {s:?}"),
+        },
+        got => bail!("Expected number, got {got}"),
+    }
+}
+
+/// Decode the payload of a case of a variant or result, `null` for none
+fn json_to_payload(value: Json, ty: Option<&ValueType>) -> Result<Option<Box<Value>>> {
+    match (ty, value) {
+        (None, Json::Null) => Ok(None),
+        (None, got) => bail!("Expected null for a case without payload, got {got}"),
+        (Some(ty), v) => json_to_wasm_value(v, ty).map(|w| Some(Box::new(w))),
+    }
+}
+
+/// The case of a variant named `name`
+fn find_case<'a>(
+    cases: &'a [(String, Option<ValueType>)],
+    name: &str,
+) -> Result<&'a (String, Option<ValueType>)> {
+    cases
+        .iter()
+        .find(|(n, _)| n == name)
+        .ok_or_else(|| anyhow!("Unknown case {name}"))
+}
+
+/// The only entry of an object encoding a case
+fn single_entry(obj: Map<String, Json>) -> Result<(String, Json)> {
+    ensure!(
+        obj.len() == 1,
+        "Expected an object with a single key, got {} keys",
+        obj.len()
+    );
+    Ok(obj.into_iter().next().unwrap())
+}
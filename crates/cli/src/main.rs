@@ -1,45 +1,109 @@
 use anyhow::{Context, Result};
-use clap::Parser;
-use std::convert::From;
+use clap::{Parser, Subcommand, ValueEnum};
+use engine::v21::V21;
+use engine::v41::V41;
+use engine::{EngineOptions, EpochTicker, Runtime, Timeout, Value, ValueType, WasiOptions};
+use envelope::{ErrorKind, Failure, ResultExt, Timing};
+use json::{json_to_wasm_value, wasm_value_to_json};
 use std::path::PathBuf;
-use wasmtime_v41::{Config, Engine, Store, component::*};
-use wasmtime_wasi_v41::p2::add_to_linker_sync;
-use wasmtime_wasi_v41::{WasiCtx, WasiCtxView, WasiView};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
 
+mod envelope;
+mod inspect;
+mod json;
 mod tests;
 
-/// Simple WasiView implementation for WasiCtx
-#[derive(Default)]
-struct MyState {
-    ctx: WasiCtx,
-    table: ResourceTable,
-}
-
-impl WasiView for MyState {
-    fn ctx(&mut self) -> WasiCtxView<'_> {
-        WasiCtxView {
-            ctx: &mut self.ctx,
-            table: &mut self.table,
-        }
-    }
-}
-
 /// CLI tool for running WebAssembly Component functions
 #[derive(Parser, Debug)]
 #[command(name = "wasmtime-cli")]
 #[command(about = "Run WebAssembly Component functions with JSON parameters", long_about = None)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Path to the WASM Component file
-    #[arg(short, long, value_name = "FILE")]
-    wasm: PathBuf,
+    #[arg(short, long, value_name = "FILE", required = true)]
+    wasm: Option<PathBuf>,
 
     /// Name of the function to execute
-    #[arg(short, long, value_name = "FUNCTION")]
-    function: String,
+    #[arg(short, long, value_name = "FUNCTION", required = true)]
+    function: Option<String>,
 
     /// JSON parameters to pass to the function (positional)
     #[arg(value_name = "JSON")]
     params: Vec<String>,
+
+    /// Enable fuel metering and report the fuel consumed by the call
+    #[arg(long)]
+    fuel: bool,
+
+    /// Interrupt instantiation and the call after this many milliseconds, using
+    /// epoch interruption
+    #[arg(long, value_name = "MS")]
+    timeout: Option<u64>,
+
+    /// Fail when a linear memory of the component grows beyond this many bytes
+    #[arg(long, value_name = "BYTES")]
+    max_memory: Option<usize>,
+
+    /// wasmtime version to run the function on, `all` runs it on each and
+    /// reports whether the results differ
+    #[arg(long, value_enum, default_value_t = EngineVersion::V41)]
+    engine: EngineVersion,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// List the exports and imports of a component with WIT-like signatures
+    Inspect {
+        /// Path to the WASM Component file
+        #[arg(short, long, value_name = "FILE")]
+        wasm: PathBuf,
+
+        /// Print a JSON envelope instead of text
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// Value of `--engine`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum EngineVersion {
+    V21,
+    V41,
+    All,
+}
+
+impl Args {
+    /// `--wasm`, required unless a subcommand is given
+    fn wasm(&self) -> &PathBuf {
+        self.wasm.as_ref().expect("--wasm is required")
+    }
+
+    /// `--function`, required unless a subcommand is given
+    fn function(&self) -> &str {
+        self.function.as_deref().expect("--function is required")
+    }
+
+    /// Engine options selected by the flags
+    fn engine_options(&self) -> EngineOptions {
+        EngineOptions {
+            consume_fuel: self.fuel,
+            epoch_interruption: self.timeout.is_some(),
+            ..Default::default()
+        }
+    }
+
+    /// WASI state options selected by the flags
+    fn wasi_options(&self) -> WasiOptions {
+        let mut options = WasiOptions::new();
+        if let Some(bytes) = self.max_memory {
+            options = options.max_memory(bytes);
+        }
+        options
+    }
 }
 
 /// Load and validate a WASM Component file
@@ -48,29 +112,36 @@ struct Args {
 ///
 /// # Arguments
 /// * `path` - Path to the WASM Component file
+/// * `options` - Options to create the engine with
 ///
 /// # Returns
-/// The loaded component and engine
-fn load_component(path: &PathBuf) -> Result<(Component, Engine)> {
+/// The loaded component and engine, a `not-found` failure if there is no
+/// such file and a `parse` failure if it isn't a valid component
+fn load_component<R: Runtime>(
+    path: &PathBuf,
+    options: &EngineOptions,
+) -> Result<(R::Component, R::Engine), Failure> {
     // Check if file exists
     if !path.exists() {
-        anyhow::bail!("WASM file not found: {}", path.display());
+        return Err(anyhow::anyhow!("WASM file not found: {}", path.display()))
+            .kind(ErrorKind::NotFound);
     }
 
     // Check if file is readable
     if !path.is_file() {
-        anyhow::bail!("Path is not a file: {}", path.display());
+        return Err(anyhow::anyhow!("Path is not a file: {}", path.display()))
+            .kind(ErrorKind::NotFound);
     }
 
     // Create wasmtime engine with component model support
-    let mut config = Config::new();
-    config.wasm_component_model(true);
-    config.wasm_simd(true);
-    let engine = Engine::new(&config).context("Failed to create wasmtime engine")?;
+    let engine = R::new_engine(options)
+        .context("Failed to create wasmtime engine")
+        .kind(ErrorKind::Engine)?;
 
     // Load the component directly from file
-    let component = Component::from_file(&engine, path)
-        .with_context(|| format!("Failed to load WASM component from: {}", path.display()))?;
+    let component = R::load_component(&engine, path)
+        .with_context(|| format!("Failed to load WASM component from: {}", path.display()))
+        .kind(ErrorKind::Parse)?;
 
     Ok((component, engine))
 }
@@ -94,102 +165,16 @@ pub fn parse_json_params(params: &[String]) -> Result<Vec<serde_json::Value>> {
     Ok(parsed_params)
 }
 
-/// Convert JSON value to WASM value based on expected type
-///
-/// # Arguments
-/// * `value` - JSON value to convert
-/// * `expect_type` - Expected WASM type for the conversion
-///
-/// # Returns
-/// The converted value as a Val
-pub fn json_to_wasm_value(value: serde_json::Value, expect_type: &Type) -> Result<Val> {
-    use serde_json::Value;
-
-    match (expect_type, value) {
-        (Type::Bool, Value::Bool(v)) => Ok(Val::Bool(v)),
-        (Type::S8, Value::Number(n)) => n
-            .as_i64()
-            .and_then(|i| i.try_into().ok())
-            .map(Val::S8)
-            .ok_or_else(|| anyhow::anyhow!("Expected s8 integer")),
-        (Type::S16, Value::Number(n)) => n
-            .as_i64()
-            .and_then(|i| i.try_into().ok())
-            .map(Val::S16)
-            .ok_or_else(|| anyhow::anyhow!("Expected s16 integer")),
-        (Type::S32, Value::Number(n)) => n
-            .as_i64()
-            .and_then(|i| i.try_into().ok())
-            .map(Val::S32)
-            .ok_or_else(|| anyhow::anyhow!("Expected s32 integer")),
-        (Type::S64, Value::Number(n)) => n
-            .as_i64()
-            .map(Val::S64)
-            .ok_or_else(|| anyhow::anyhow!("Expected s64 integer")),
-        (Type::U8, Value::Number(n)) => n
-            .as_u64()
-            .and_then(|u| u.try_into().ok())
-            .map(Val::U8)
-            .ok_or_else(|| anyhow::anyhow!("Expected u8 integer")),
-        (Type::U16, Value::Number(n)) => n
-            .as_u64()
-            .and_then(|u| u.try_into().ok())
-            .map(Val::U16)
-            .ok_or_else(|| anyhow::anyhow!("Expected u16 integer")),
-        (Type::U32, Value::Number(n)) => n
-            .as_u64()
-            .and_then(|u| u.try_into().ok())
-            .map(Val::U32)
-            .ok_or_else(|| anyhow::anyhow!("Expected u32 integer")),
-        (Type::U64, Value::Number(n)) => n
-            .as_u64()
-            .map(Val::U64)
-            .ok_or_else(|| anyhow::anyhow!("Expected u64 integer")),
-        (Type::Float32, Value::Number(n)) => n
-            .as_f64()
-            .map(|f| Val::Float32(f as f32))
-            .ok_or_else(|| anyhow::anyhow!("Expected number")),
-        (Type::Float64, Value::Number(n)) => n
-            .as_f64()
-            .map(Val::Float64)
-            .ok_or_else(|| anyhow::anyhow!("Expected number")),
-        (Type::Char, Value::String(s)) => {
-            if s.len() == 1 {
-                Ok(Val::Char(s.chars().next().unwrap()))
-            } else {
-                anyhow::bail!("Expected single character string")
-            }
-        }
-        (Type::String, Value::String(s)) => Ok(Val::String(s)),
-        (Type::List(v), Value::Array(arr)) => {
-            let elem_type = v.ty();
-
-            // Convert JSON Array to WASM List
-            let mut wasm_values = Vec::new();
-            for elem in arr {
-                wasm_values.push(json_to_wasm_value(elem, &elem_type)?);
-            }
-
-            Ok(Val::List(wasm_values))
-        }
-        (Type::Record(r), Value::Object(mut obj)) => {
-            let mut wasm_values = Vec::new();
-
-            for f in r.fields() {
-                let v = obj
-                    .remove(f.name)
-                    .ok_or_else(|| anyhow::anyhow!("Field {} not found", f.name))?;
-
-                let w = json_to_wasm_value(v, &f.ty)
-                    .with_context(|| format!("bad field {}", f.name))?;
-
-                wasm_values.push((f.name.to_owned(), w));
-            }
-
-            Ok(Val::Record(wasm_values))
-        }
-        (expect, got) => anyhow::bail!("expect {expect:?}, got {got:?}"),
-    }
+/// Outcome of a successful function execution
+struct Execution {
+    results: Vec<Value>,
+    /// Types of the results, as declared by the function
+    result_types: Vec<ValueType>,
+    /// Fuel consumed by the call, when fuel metering is enabled
+    fuel_consumed: Option<u64>,
+    /// Time spent linking and instantiating, then calling
+    instantiate: Duration,
+    call: Duration,
 }
 
 /// Execute a WASM function with parameters
@@ -197,114 +182,219 @@ pub fn json_to_wasm_value(value: serde_json::Value, expect_type: &Type) -> Resul
 /// # Arguments
 /// * `component` - The loaded component
 /// * `engine` - The wasmtime engine
+/// * `options` - Options the engine was created with
+/// * `wasi_options` - Options of the WASI state of the store
 /// * `function_name` - Name of the function to execute (supports "interface#function" format)
 /// * `params` - WASM values to pass as parameters
+/// * `timeout` - Interrupt instantiation and the call after it, needs
+///   `options.epoch_interruption`
 ///
 /// # Returns
-/// The result of the function execution
-fn execute_function(
-    component: &Component,
-    engine: &Engine,
+/// The result of the function execution, or a failure of the kind of the
+/// phase that failed
+fn execute_function<R: Runtime + 'static>(
+    component: &R::Component,
+    engine: &R::Engine,
+    options: &EngineOptions,
+    wasi_options: &WasiOptions,
     function_name: &str,
     params_json: Vec<serde_json::Value>,
-) -> Result<Vec<Val>> {
-    let mut store = Store::new(engine, MyState::default());
-    let mut linker = Linker::new(engine);
-
-    // Add WASI to the linker
-    add_to_linker_sync(&mut linker).context("Failed to link WASI")?;
+    timeout: Option<Duration>,
+) -> Result<Execution, Failure> {
+    let start = Instant::now();
+    let mut store = R::new_store_with(engine, wasi_options)
+        .context("Failed to create store")
+        .kind(ErrorKind::Instantiate)?;
+
+    // Add WASI to the linker and resolve the imports of the component
+    let linker = R::new_linker(engine, options)
+        .context("Failed to link WASI")
+        .kind(ErrorKind::Link)?;
+    let pre_instance = R::link_instantiate_pre(&linker, component)
+        .context("Failed to link component")
+        .kind(ErrorKind::Link)?;
+
+    // The deadline covers instantiation too, e.g. a start function that loops
+    let _ticker = timeout.map(|timeout| {
+        R::set_timeout(&mut store, timeout);
+        EpochTicker::start::<R>(engine)
+    });
 
     // Instantiate the component
-    let instance = linker
-        .instantiate(&mut store, component)
-        .context("Failed to instantiate component")?;
+    let instance = match R::instantiate(&pre_instance, &mut store) {
+        Ok(instance) => instance,
+        Err(error) => {
+            let kind = if error.is::<Timeout>() {
+                ErrorKind::Timeout
+            } else {
+                ErrorKind::Instantiate
+            };
+            return Err(error)
+                .context("Failed to instantiate component")
+                .kind(kind);
+        }
+    };
+    let instantiate = start.elapsed();
+
     // Parse function name to support "interface#function" format
-    let func = find_func(&instance, &mut store, function_name).context("find func")?;
+    let func = R::find_func(&instance, &mut store, function_name)
+        .context("find func")
+        .kind(ErrorKind::NotFound)?;
+
+    let expect = R::param_types(&func, &store);
+    if expect.len() != params_json.len() {
+        return Err(anyhow::anyhow!(
+            "expect {} params, got {}",
+            expect.len(),
+            params_json.len()
+        ))
+        .kind(ErrorKind::TypeMismatch);
+    }
 
-    let (params, mut results) = {
-        let sig = func.ty(&store);
+    let mut params = Vec::with_capacity(expect.len());
+    for (i, (t, v)) in expect.iter().zip(params_json).enumerate() {
+        let w = json_to_wasm_value(v, t)
+            .with_context(|| format!("bad param {}", i + 1))
+            .kind(ErrorKind::TypeMismatch)?;
+        params.push(R::Val::from(w));
+    }
 
-        let expect = sig.params();
-        let mut params = Vec::with_capacity(expect.len());
-        for ((name, t), v) in expect.zip(params_json.into_iter()) {
-            let w = json_to_wasm_value(v, &t).with_context(|| format!("bad param {name}"))?;
-            params.push(w);
-        }
+    let result_types = R::result_types(&func, &store);
+    let mut results = vec![R::Val::from(Value::Bool(false)); result_types.len()];
+
+    let start = Instant::now();
+    let fuel_before = R::fuel(&store);
+    if let Err(error) = R::call(&func, &mut store, &params, &mut results) {
+        let kind = if error.is::<Timeout>() {
+            ErrorKind::Timeout
+        } else if R::is_trap(&error) {
+            ErrorKind::Trap
+        } else {
+            // the parameters were checked against the signature above
+            ErrorKind::Host
+        };
+        return Err(error)
+            .with_context(|| format!("Failed to call function '{}'", function_name))
+            .kind(kind);
+    }
+    let fuel_consumed = fuel_before.zip(R::fuel(&store)).map(|(b, a)| b - a);
+    let call = start.elapsed();
+
+    let results = results
+        .into_iter()
+        .map(R::Val::try_into)
+        .collect::<Result<Vec<Value>>>()
+        .context("Failed to convert results")
+        .kind(ErrorKind::TypeMismatch)?;
+
+    Ok(Execution {
+        results,
+        result_types,
+        fuel_consumed,
+        instantiate,
+        call,
+    })
+}
 
-        let results = vec![Val::Bool(false); sig.results().len()];
+fn main() -> ExitCode {
+    let args = Args::parse();
+    if let Some(Command::Inspect { wasm, json }) = &args.command {
+        return run_inspect(wasm, *json);
+    }
 
-        (params, results)
+    // Every invocation prints an envelope: to stdout on success, to stderr on
+    // failure, with an exit code telling the kind of failure
+    let (envelope, failed) = match args.engine {
+        EngineVersion::V21 => single(run::<V21>(&args)),
+        EngineVersion::V41 => single(run::<V41>(&args)),
+        EngineVersion::All => run_all(&args),
     };
-
-    func.call(&mut store, &params, &mut results)
-        .with_context(|| format!("Failed to call function '{}'", function_name))?;
-
-    Ok(results)
+    match failed {
+        None => {
+            println!("{envelope:#}");
+            ExitCode::SUCCESS
+        }
+        Some(kind) => {
+            eprintln!("{envelope:#}");
+            ExitCode::from(kind.exit_code())
+        }
+    }
 }
 
-/// Convert WASM value to JSON value
-///
-/// # Arguments
-/// * `val` - WASM value to convert
-///
-/// # Returns
-/// JSON representation of the value
-fn wasm_value_to_json(val: &Val) -> serde_json::Value {
-    match val {
-        Val::Bool(b) => serde_json::Value::Bool(*b),
-        Val::U8(u) => serde_json::Value::Number(serde_json::Number::from(*u)),
-        Val::S32(i) => serde_json::Value::Number(serde_json::Number::from(*i)),
-        Val::U32(u) => serde_json::Value::Number(serde_json::Number::from(*u)),
-        Val::S64(i) => serde_json::json!(*i),
-        Val::U64(u) => serde_json::json!(*u),
-        Val::Float32(f) => serde_json::Value::Number(
-            serde_json::Number::from_f64(*f as f64).unwrap_or(serde_json::Number::from(0)),
-        ),
-        Val::Float64(f) => serde_json::Value::Number(
-            serde_json::Number::from_f64(*f).unwrap_or(serde_json::Number::from(0)),
-        ),
-        Val::Char(c) => serde_json::json!(c.to_string()),
-        Val::String(s) => serde_json::Value::String(s.clone()),
-        Val::List(items) => {
-            let json_items: Vec<serde_json::Value> = items.iter().map(wasm_value_to_json).collect();
-            serde_json::json!(json_items)
+/// Print the exports and imports of the component, as text or as a JSON
+/// envelope, failures being reported like those of a run
+fn run_inspect(wasm: &PathBuf, json: bool) -> ExitCode {
+    match inspect::inspect(wasm) {
+        Ok(inspection) => {
+            if json {
+                println!("{:#}", inspection.to_json());
+            } else {
+                print!("{inspection}");
+            }
+            ExitCode::SUCCESS
+        }
+        Err(failure) => {
+            eprintln!("{:#}", failure.to_json());
+            ExitCode::from(failure.kind.exit_code())
         }
-        Val::Result(result) => match result.as_ref() {
-            Ok(Some(ok_val)) => serde_json::json!({ "ok": wasm_value_to_json(ok_val) }),
-            Ok(None) => serde_json::json!("_"),
-            Err(Some(err_val)) => serde_json::json!({ "err": wasm_value_to_json(err_val) }),
-            Err(None) => serde_json::json!("_"),
-        },
-        _ => serde_json::json!(format!("{:?}", val)),
     }
 }
 
-fn main() -> anyhow::Result<()> {
-    let args = Args::parse();
-
-    // Run with error handling
-    run(args).context("run")
+/// The envelope of a run on a single engine, along with the kind of its
+/// failure
+fn single(run: Result<serde_json::Value, Failure>) -> (serde_json::Value, Option<ErrorKind>) {
+    match run {
+        Ok(envelope) => (envelope, None),
+        Err(failure) => (failure.to_json(), Some(failure.kind)),
+    }
 }
 
-fn run(args: Args) -> Result<()> {
-    println!("WASM file: {:?}", args.wasm);
-    println!("Function: {}", args.function);
-    println!("Parameters: {:?}", args.params);
+/// Run the function on every engine of `--engine all`
+///
+/// # Returns
+/// The comparison envelope, along with the kind of the first failure
+fn run_all(args: &Args) -> (serde_json::Value, Option<ErrorKind>) {
+    let runs = [(V21::NAME, run::<V21>(args)), (V41::NAME, run::<V41>(args))];
+    let failed = runs
+        .iter()
+        .find_map(|(_, run)| run.as_ref().err().map(|failure| failure.kind));
+    (envelope::comparison_json(&runs), failed)
+}
 
+fn run<R: Runtime + 'static>(args: &Args) -> Result<serde_json::Value, Failure> {
     // Parse JSON parameters from command line arguments
-    let params = parse_json_params(&args.params).context("parse params")?;
-    println!("Parsed parameters: {:?}", params);
+    let params = parse_json_params(&args.params)
+        .context("parse params")
+        .kind(ErrorKind::Parse)?;
 
     // Load WASM component file (must be a pre-packaged WASM component)
-    let (component, engine) = load_component(&args.wasm)?;
-    println!("Successfully loaded WASM component");
+    let start = Instant::now();
+    let options = args.engine_options();
+    let (component, engine) = load_component::<R>(args.wasm(), &options)?;
+    let load = start.elapsed();
 
     // Execute the function
-    let result =
-        execute_function(&component, &engine, &args.function, params).context("execute")?;
-
+    let timeout = args.timeout.map(Duration::from_millis);
+    let execution = execute_function::<R>(
+        &component,
+        &engine,
+        &options,
+        &args.wasi_options(),
+        args.function(),
+        params,
+        timeout,
+    )?;
     // Convert WASM results to JSON
-    let json_results: Vec<serde_json::Value> = result.iter().map(wasm_value_to_json).collect();
+    let json_results = execution
+        .results
+        .iter()
+        .zip(&execution.result_types)
+        .enumerate()
+        .map(|(i, (val, ty))| {
+            wasm_value_to_json(val, ty).with_context(|| format!("bad result {i}"))
+        })
+        .collect::<Result<Vec<_>>>()
+        .kind(ErrorKind::TypeMismatch)?;
 
     // Create output
     let out = if json_results.len() == 1 {
@@ -313,24 +403,14 @@ fn run(args: Args) -> Result<()> {
         serde_json::json!(json_results)
     };
 
-    println!("{}", serde_json::to_string(&out)?);
-
-    Ok(())
-}
-
-fn find_func<T>(i: &Instance, mut store: &mut Store<T>, name: &str) -> Result<Func> {
-    let (interface, func_name) = name
-        .split_once('#')
-        .ok_or_else(|| anyhow::anyhow!("must in form of 'interface#func'"))?;
-
-    let ii = i
-        .get_export_index(&mut *store, None, interface)
-        .ok_or_else(|| anyhow::anyhow!("miss interface: {interface}"))?;
-
-    let fi = i
-        .get_export_index(&mut store, Some(&ii), func_name)
-        .with_context(|| format!("miss export-index for func '{func_name}'"))?;
-
-    i.get_func(&mut store, fi)
-        .with_context(|| format!("miss func '{func_name}'"))
+    let timing = Timing {
+        load,
+        instantiate: execution.instantiate,
+        call: execution.call,
+    };
+    Ok(envelope::success_json(
+        out,
+        &timing,
+        execution.fuel_consumed,
+    ))
 }
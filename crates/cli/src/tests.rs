@@ -1,10 +1,20 @@
 #[cfg(test)]
 mod tests {
-    use crate::{json_to_wasm_value, load_component, parse_json_params, wasm_value_to_json};
+    use crate::envelope::ErrorKind;
+    use crate::inspect::{Item, inspect, wit};
+    use crate::json::{json_to_wasm_value, wasm_value_to_json};
+    use crate::{execute_function, load_component, parse_json_params};
+    use engine::v21::V21;
+    use engine::v41::V41;
+    use engine::v41::component::Val;
+    use engine::{EngineOptions, Profile, Strategy, Value, ValueType, WasiOptions};
+    use proptest::prelude::*;
+    use proptest::strategy::Union;
+    use proptest::test_runner::TestRunner;
+    use serde_json::json;
     use std::io::Write;
     use std::path::PathBuf;
     use tempfile::NamedTempFile;
-    use wasmtime_v41::component::Val;
 
     #[test]
     fn test_parse_json_params_valid() {
@@ -30,40 +40,72 @@ mod tests {
         assert!(result.is_err());
     }
 
+    /// `option<ty>`
+    fn option(ty: ValueType) -> ValueType {
+        ValueType::Option(Box::new(ty))
+    }
+
+    /// Owned copies of `names`
+    fn strings(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    /// `result<ok, err>`
+    fn result(ok: Option<ValueType>, err: Option<ValueType>) -> ValueType {
+        ValueType::Result {
+            ok: ok.map(Box::new),
+            err: err.map(Box::new),
+        }
+    }
+
     #[test]
     fn test_json_to_wasm_value_bool() {
-        let json_val = serde_json::Value::Bool(true);
-        let result = json_to_wasm_value(&json_val, 0);
-        assert!(result.is_ok());
-        matches!(result.unwrap(), Val::Bool(true));
+        let result = json_to_wasm_value(json!(true), &ValueType::Bool);
+        assert!(matches!(result.unwrap(), Value::Bool(true)));
     }
 
     #[test]
     fn test_json_to_wasm_value_number_i32() {
-        let json_val = serde_json::Value::Number(42.into());
-        let result = json_to_wasm_value(&json_val, 0);
-        assert!(result.is_ok());
-        matches!(result.unwrap(), Val::S32(42));
+        let result = json_to_wasm_value(json!(42), &ValueType::S32);
+        assert!(matches!(result.unwrap(), Value::S32(42)));
+    }
+
+    #[test]
+    fn test_json_to_wasm_value_number_out_of_range() {
+        assert!(json_to_wasm_value(json!(256), &ValueType::U8).is_err());
+        assert!(json_to_wasm_value(json!(-1), &ValueType::U32).is_err());
     }
 
     #[test]
     fn test_json_to_wasm_value_number_f64() {
-        let json_val = serde_json::Value::Number(serde_json::Number::from_f64(3.14).unwrap());
-        let result = json_to_wasm_value(&json_val, 0);
-        assert!(result.is_ok());
-        if let Val::Float64(f) = result.unwrap() {
-            assert!((f - 3.14).abs() < 0.01);
-        } else {
-            panic!("Expected Float64");
-        }
+        let result = json_to_wasm_value(json!(2.5), &ValueType::Float64);
+        assert_eq!(result.unwrap(), Value::Float64(2.5));
+    }
+
+    #[test]
+    fn test_json_to_wasm_value_float_non_finite() {
+        let result = json_to_wasm_value(json!("NaN"), &ValueType::Float32);
+        assert!(matches!(result.unwrap(), Value::Float32(f) if f.is_nan()));
+
+        let result = json_to_wasm_value(json!("-Infinity"), &ValueType::Float64);
+        assert!(matches!(result.unwrap(), Value::Float64(f) if f == f64::NEG_INFINITY));
+
+        assert!(json_to_wasm_value(json!("1.5"), &ValueType::Float64).is_err());
+    }
+
+    #[test]
+    fn test_json_to_wasm_value_char() {
+        let result = json_to_wasm_value(json!("é"), &ValueType::Char);
+        assert!(matches!(result.unwrap(), Value::Char('é')));
+
+        assert!(json_to_wasm_value(json!("ab"), &ValueType::Char).is_err());
+        assert!(json_to_wasm_value(json!(""), &ValueType::Char).is_err());
     }
 
     #[test]
     fn test_json_to_wasm_value_string() {
-        let json_val = serde_json::Value::String("test".to_string());
-        let result = json_to_wasm_value(&json_val, 0);
-        assert!(result.is_ok());
-        if let Val::String(s) = result.unwrap() {
+        let result = json_to_wasm_value(json!("test"), &ValueType::String);
+        if let Value::String(s) = result.unwrap() {
             assert_eq!(s, "test");
         } else {
             panic!("Expected String");
@@ -71,44 +113,378 @@ mod tests {
     }
 
     #[test]
-    fn test_json_to_wasm_value_array_unsupported() {
-        let json_val = serde_json::Value::Array(vec![]);
-        let result = json_to_wasm_value(&json_val, 0);
-        assert!(result.is_err());
+    fn test_json_to_wasm_value_mismatch() {
+        assert!(json_to_wasm_value(json!([]), &ValueType::String).is_err());
+        assert!(json_to_wasm_value(json!({}), &ValueType::U32).is_err());
     }
 
     #[test]
-    fn test_json_to_wasm_value_object_unsupported() {
-        let json_val = serde_json::Value::Object(serde_json::Map::new());
-        let result = json_to_wasm_value(&json_val, 0);
-        assert!(result.is_err());
+    fn test_json_to_wasm_value_record() {
+        let ty = ValueType::Record(vec![
+            ("name".to_string(), ValueType::String),
+            ("size".to_string(), ValueType::U32),
+        ]);
+
+        let result = json_to_wasm_value(json!({"size": 1, "name": "x"}), &ty).unwrap();
+        assert_eq!(
+            result,
+            Value::Record(vec![
+                ("name".to_string(), Value::String("x".to_string())),
+                ("size".to_string(), Value::U32(1)),
+            ])
+        );
+
+        assert!(json_to_wasm_value(json!({"name": "x"}), &ty).is_err());
+        assert!(json_to_wasm_value(json!({"name": "x", "size": 1, "extra": 2}), &ty).is_err());
+    }
+
+    #[test]
+    fn test_json_to_wasm_value_tuple() {
+        let ty = ValueType::Tuple(vec![ValueType::U32, ValueType::String]);
+
+        let result = json_to_wasm_value(json!([1, "a"]), &ty).unwrap();
+        assert_eq!(
+            result,
+            Value::Tuple(vec![Value::U32(1), Value::String("a".to_string())])
+        );
+
+        assert!(json_to_wasm_value(json!([1]), &ty).is_err());
+        assert!(json_to_wasm_value(json!([1, "a", 2]), &ty).is_err());
+    }
+
+    #[test]
+    fn test_json_to_wasm_value_variant() {
+        let ty = ValueType::Variant(vec![
+            ("circle".to_string(), Some(ValueType::Float64)),
+            ("empty".to_string(), None),
+        ]);
+
+        let result = json_to_wasm_value(json!({"circle": 1.5}), &ty).unwrap();
+        assert_eq!(
+            result,
+            Value::Variant("circle".to_string(), Some(Box::new(Value::Float64(1.5))))
+        );
+
+        let expected = Value::Variant("empty".to_string(), None);
+        assert_eq!(
+            json_to_wasm_value(json!({"empty": null}), &ty).unwrap(),
+            expected
+        );
+        assert_eq!(json_to_wasm_value(json!("empty"), &ty).unwrap(), expected);
+
+        assert!(json_to_wasm_value(json!("circle"), &ty).is_err());
+        assert!(json_to_wasm_value(json!({"empty": 1}), &ty).is_err());
+        assert!(json_to_wasm_value(json!({"square": 1.5}), &ty).is_err());
+        assert!(json_to_wasm_value(json!({"circle": 1.5, "empty": null}), &ty).is_err());
+    }
+
+    #[test]
+    fn test_json_to_wasm_value_enum() {
+        let ty = ValueType::Enum(strings(&["red", "green"]));
+
+        let result = json_to_wasm_value(json!("green"), &ty).unwrap();
+        assert_eq!(result, Value::Enum("green".to_string()));
+
+        assert!(json_to_wasm_value(json!("blue"), &ty).is_err());
+    }
+
+    #[test]
+    fn test_json_to_wasm_value_option() {
+        let ty = option(ValueType::U32);
+
+        assert_eq!(
+            json_to_wasm_value(json!(null), &ty).unwrap(),
+            Value::Option(None)
+        );
+        assert_eq!(
+            json_to_wasm_value(json!(7), &ty).unwrap(),
+            Value::Option(Some(Box::new(Value::U32(7))))
+        );
+    }
+
+    #[test]
+    fn test_json_to_wasm_value_nested_option() {
+        let ty = option(option(ValueType::U32));
+
+        assert_eq!(
+            json_to_wasm_value(json!(null), &ty).unwrap(),
+            Value::Option(None)
+        );
+        assert_eq!(
+            json_to_wasm_value(json!({"some": null}), &ty).unwrap(),
+            Value::Option(Some(Box::new(Value::Option(None))))
+        );
+        assert_eq!(
+            json_to_wasm_value(json!({"some": 7}), &ty).unwrap(),
+            Value::Option(Some(Box::new(Value::Option(Some(Box::new(Value::U32(7)))))))
+        );
+
+        assert!(json_to_wasm_value(json!(7), &ty).is_err());
+    }
+
+    #[test]
+    fn test_json_to_wasm_value_result() {
+        let ty = result(Some(ValueType::U32), Some(ValueType::String));
+
+        assert_eq!(
+            json_to_wasm_value(json!({"ok": 1}), &ty).unwrap(),
+            Value::Result(Ok(Some(Box::new(Value::U32(1)))))
+        );
+        assert_eq!(
+            json_to_wasm_value(json!({"err": "boom"}), &ty).unwrap(),
+            Value::Result(Err(Some(Box::new(Value::String("boom".to_string())))))
+        );
+
+        let ty = result(None, None);
+        assert_eq!(
+            json_to_wasm_value(json!({"ok": null}), &ty).unwrap(),
+            Value::Result(Ok(None))
+        );
+        assert_eq!(
+            json_to_wasm_value(json!({"err": null}), &ty).unwrap(),
+            Value::Result(Err(None))
+        );
+
+        assert!(json_to_wasm_value(json!({"value": null}), &ty).is_err());
+    }
+
+    #[test]
+    fn test_json_to_wasm_value_flags() {
+        let ty = ValueType::Flags(strings(&["read", "write", "exec"]));
+
+        let result = json_to_wasm_value(json!(["read", "exec"]), &ty).unwrap();
+        assert_eq!(
+            result,
+            Value::Flags(vec!["read".to_string(), "exec".to_string()])
+        );
+
+        assert!(json_to_wasm_value(json!(["delete"]), &ty).is_err());
     }
 
     #[test]
     fn test_wasm_value_to_json_bool() {
-        let wasm_val = Val::Bool(true);
-        let result = wasm_value_to_json(&wasm_val);
+        let wasm_val = Value::Bool(true);
+        let result = wasm_value_to_json(&wasm_val, &ValueType::Bool).unwrap();
         assert_eq!(result, serde_json::Value::Bool(true));
     }
 
     #[test]
     fn test_wasm_value_to_json_s32() {
-        let wasm_val = Val::S32(42);
-        let result = wasm_value_to_json(&wasm_val);
+        let wasm_val = Value::S32(42);
+        let result = wasm_value_to_json(&wasm_val, &ValueType::S32).unwrap();
         assert_eq!(result, 42);
     }
 
     #[test]
     fn test_wasm_value_to_json_string() {
-        let wasm_val = Val::String("test".to_string());
-        let result = wasm_value_to_json(&wasm_val);
+        let wasm_val = Value::String("test".to_string());
+        let result = wasm_value_to_json(&wasm_val, &ValueType::String).unwrap();
         assert_eq!(result, "test");
     }
 
+    #[test]
+    fn test_wasm_value_to_json_float_non_finite() {
+        let result = wasm_value_to_json(&Value::Float32(f32::NAN), &ValueType::Float32).unwrap();
+        assert_eq!(result, "NaN");
+
+        let result =
+            wasm_value_to_json(&Value::Float64(f64::INFINITY), &ValueType::Float64).unwrap();
+        assert_eq!(result, "Infinity");
+    }
+
+    #[test]
+    fn test_wasm_value_to_json_result_without_payload() {
+        let ty = result(None, None);
+
+        let result = wasm_value_to_json(&Value::Result(Ok(None)), &ty).unwrap();
+        assert_eq!(result, json!({"ok": null}));
+
+        let result = wasm_value_to_json(&Value::Result(Err(None)), &ty).unwrap();
+        assert_eq!(result, json!({"err": null}));
+    }
+
+    #[test]
+    fn test_wasm_value_to_json_nested_option() {
+        let ty = option(option(ValueType::U32));
+
+        let wasm_val = Value::Option(Some(Box::new(Value::Option(None))));
+        let result = wasm_value_to_json(&wasm_val, &ty).unwrap();
+        assert_eq!(result, json!({"some": null}));
+    }
+
+    #[test]
+    fn test_wasm_value_to_json_variant() {
+        let ty = ValueType::Variant(vec![
+            ("circle".to_string(), Some(ValueType::Float64)),
+            ("empty".to_string(), None),
+        ]);
+
+        let wasm_val = Value::Variant("empty".to_string(), None);
+        let result = wasm_value_to_json(&wasm_val, &ty).unwrap();
+        assert_eq!(result, json!({"empty": null}));
+    }
+
+    #[test]
+    fn test_wasm_value_to_json_mismatch() {
+        assert!(wasm_value_to_json(&Value::S32(1), &ValueType::U32).is_err());
+
+        let ty = ValueType::Enum(strings(&["red", "green"]));
+        assert!(wasm_value_to_json(&Value::Enum("blue".to_string()), &ty).is_err());
+    }
+
+    /// Any value of type `ty`
+    fn arb_val(ty: &ValueType) -> BoxedStrategy<Value> {
+        match ty {
+            ValueType::Bool => any::<bool>().prop_map(Value::Bool).boxed(),
+            ValueType::S8 => any::<i8>().prop_map(Value::S8).boxed(),
+            ValueType::U8 => any::<u8>().prop_map(Value::U8).boxed(),
+            ValueType::S16 => any::<i16>().prop_map(Value::S16).boxed(),
+            ValueType::U16 => any::<u16>().prop_map(Value::U16).boxed(),
+            ValueType::S32 => any::<i32>().prop_map(Value::S32).boxed(),
+            ValueType::U32 => any::<u32>().prop_map(Value::U32).boxed(),
+            ValueType::S64 => any::<i64>().prop_map(Value::S64).boxed(),
+            ValueType::U64 => any::<u64>().prop_map(Value::U64).boxed(),
+            ValueType::Float32 => prop_oneof![
+                any::<f32>(),
+                Just(f32::NAN),
+                Just(f32::INFINITY),
+                Just(f32::NEG_INFINITY),
+            ]
+            .prop_map(Value::Float32)
+            .boxed(),
+            ValueType::Float64 => prop_oneof![
+                any::<f64>(),
+                Just(f64::NAN),
+                Just(f64::INFINITY),
+                Just(f64::NEG_INFINITY),
+            ]
+            .prop_map(Value::Float64)
+            .boxed(),
+            ValueType::Char => any::<char>().prop_map(Value::Char).boxed(),
+            ValueType::String => any::<String>().prop_map(Value::String).boxed(),
+            ValueType::List(ty) => prop::collection::vec(arb_val(ty), 0..4)
+                .prop_map(Value::List)
+                .boxed(),
+            ValueType::Record(fields) => {
+                let names: Vec<String> = fields.iter().map(|(name, _)| name.clone()).collect();
+                let fields: Vec<_> = fields.iter().map(|(_, ty)| arb_val(ty)).collect();
+                fields
+                    .prop_map(move |vals| Value::Record(names.iter().cloned().zip(vals).collect()))
+                    .boxed()
+            }
+            ValueType::Tuple(types) => {
+                let items: Vec<_> = types.iter().map(arb_val).collect();
+                items.prop_map(Value::Tuple).boxed()
+            }
+            ValueType::Variant(cases) => {
+                let cases: Vec<_> = cases
+                    .iter()
+                    .map(|(name, ty)| {
+                        let name = name.clone();
+                        arb_payload(ty.as_ref())
+                            .prop_map(move |payload| Value::Variant(name.clone(), payload))
+                            .boxed()
+                    })
+                    .collect();
+                Union::new(cases).boxed()
+            }
+            ValueType::Enum(names) => prop::sample::select(names.clone())
+                .prop_map(Value::Enum)
+                .boxed(),
+            ValueType::Option(ty) => prop::option::of(arb_val(ty))
+                .prop_map(|v| Value::Option(v.map(Box::new)))
+                .boxed(),
+            ValueType::Result { ok, err } => prop_oneof![
+                arb_payload(ok.as_deref()).prop_map(Ok),
+                arb_payload(err.as_deref()).prop_map(Err),
+            ]
+            .prop_map(Value::Result)
+            .boxed(),
+            ValueType::Flags(names) => prop::sample::subsequence(names.clone(), 0..=names.len())
+                .prop_map(Value::Flags)
+                .boxed(),
+            other => panic!("No JSON encoding for {other:?}"),
+        }
+    }
+
+    /// Any payload of a case of type `ty`
+    fn arb_payload(ty: Option<&ValueType>) -> BoxedStrategy<Option<Box<Value>>> {
+        match ty {
+            Some(ty) => arb_val(ty).prop_map(|v| Some(Box::new(v))).boxed(),
+            None => Just(None).boxed(),
+        }
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let types = [
+            ValueType::Bool,
+            ValueType::S8,
+            ValueType::U8,
+            ValueType::S16,
+            ValueType::U16,
+            ValueType::S32,
+            ValueType::U32,
+            ValueType::S64,
+            ValueType::U64,
+            ValueType::Float32,
+            ValueType::Float64,
+            ValueType::Char,
+            ValueType::String,
+            ValueType::List(Box::new(ValueType::Tuple(vec![
+                ValueType::S8,
+                ValueType::U16,
+                ValueType::Char,
+            ]))),
+            ValueType::Record(vec![
+                ("a".to_string(), ValueType::S16),
+                ("b".to_string(), ValueType::Float32),
+                ("c".to_string(), option(option(ValueType::U64))),
+                (
+                    "d".to_string(),
+                    result(Some(ValueType::String), Some(ValueType::U8)),
+                ),
+                (
+                    "e".to_string(),
+                    ValueType::List(Box::new(ValueType::Float64)),
+                ),
+            ]),
+            ValueType::Variant(vec![
+                ("a".to_string(), None),
+                ("b".to_string(), Some(ValueType::S64)),
+                ("c".to_string(), Some(option(ValueType::String))),
+            ]),
+            ValueType::Enum(strings(&["red", "green", "blue"])),
+            ValueType::Flags(strings(&["read", "write", "exec"])),
+            option(ValueType::List(Box::new(ValueType::U8))),
+            option(option(option(ValueType::Bool))),
+            option(result(Some(ValueType::U32), None)),
+            result(None, None),
+            result(None, Some(ValueType::Tuple(vec![]))),
+        ];
+
+        for ty in types {
+            let mut runner = TestRunner::default();
+            runner
+                .run(&arb_val(&ty), |val| {
+                    // Through the text of the JSON, as printed by the CLI
+                    let json = wasm_value_to_json(&val, &ty).unwrap();
+                    let text = serde_json::to_string(&json).unwrap();
+                    let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+
+                    // Compared as wasmtime values, which treat NaNs as equal
+                    let decoded = json_to_wasm_value(json, &ty).unwrap();
+                    prop_assert_eq!(Val::from(decoded), Val::from(val), "{}", text);
+                    Ok(())
+                })
+                .unwrap_or_else(|e| panic!("{ty:?}: {e}"));
+        }
+    }
+
     #[test]
     fn test_load_component_file_not_found() {
         let path = PathBuf::from("/nonexistent/file.wasm");
-        let result = load_component(&path);
+        let result = load_component::<V41>(&path, &EngineOptions::default());
         match result {
             Ok(_) => panic!("Expected error"),
             Err(e) => assert!(e.to_string().contains("not found")),
@@ -120,10 +496,152 @@ mod tests {
         let mut temp_file = NamedTempFile::new().unwrap();
         writeln!(temp_file, "invalid wasm content").unwrap();
 
-        let result = load_component(&temp_file.path().to_path_buf());
+        let result =
+            load_component::<V41>(&temp_file.path().to_path_buf(), &EngineOptions::default());
         match result {
             Ok(_) => panic!("Expected error"),
             Err(e) => assert!(e.to_string().contains("Failed to load WASM component")),
         }
     }
+
+    #[test]
+    fn test_load_component_engine_failure() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let options = EngineOptions {
+            profile: Profile {
+                strategy: Strategy::Winch,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // v21 has no Winch support
+        let failure = load_component::<V21>(&temp_file.path().to_path_buf(), &options).unwrap_err();
+        assert_eq!(failure.kind, ErrorKind::Engine);
+    }
+
+    #[test]
+    fn test_execute_function_trap() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        write!(
+            temp_file,
+            r#"(component
+                (core module $m
+                    (func (export "run") unreachable))
+                (core instance $i (instantiate $m))
+                (func (export "run") (canon lift (core func $i "run"))))"#
+        )
+        .unwrap();
+
+        let options = EngineOptions::default();
+        let (component, engine) =
+            load_component::<V41>(&temp_file.path().to_path_buf(), &options).unwrap();
+        let failure = execute_function::<V41>(
+            &component,
+            &engine,
+            &options,
+            &WasiOptions::default(),
+            "run",
+            vec![],
+            None,
+        )
+        .err()
+        .unwrap();
+        assert_eq!(failure.kind, ErrorKind::Trap);
+    }
+
+    #[test]
+    fn test_wit() {
+        let cases = [
+            (ValueType::Float32, "f32"),
+            (ValueType::List(Box::new(ValueType::U8)), "list<u8>"),
+            (
+                ValueType::Tuple(vec![ValueType::String, ValueType::Char]),
+                "tuple<string, char>",
+            ),
+            (
+                ValueType::Record(vec![
+                    ("a".into(), ValueType::U32),
+                    ("b".into(), option(ValueType::String)),
+                ]),
+                "record { a: u32, b: option<string> }",
+            ),
+            (
+                ValueType::Variant(vec![("c".into(), Some(ValueType::S64)), ("d".into(), None)]),
+                "variant { c(s64), d }",
+            ),
+            (ValueType::Enum(strings(&["x", "y"])), "enum { x, y }"),
+            (ValueType::Flags(strings(&["r", "w"])), "flags { r, w }"),
+            (result(None, None), "result"),
+            (result(None, Some(ValueType::String)), "result<_, string>"),
+            (
+                result(
+                    Some(ValueType::List(Box::new(ValueType::U8))),
+                    Some(ValueType::String),
+                ),
+                "result<list<u8>, string>",
+            ),
+            (ValueType::Own, "own<_>"),
+            (ValueType::Future(None), "future"),
+            (
+                ValueType::Stream(Some(Box::new(ValueType::U8))),
+                "stream<u8>",
+            ),
+            (ValueType::ErrorContext, "error-context"),
+        ];
+        for (ty, expected) in cases {
+            assert_eq!(wit(&ty), expected, "{ty:?}");
+        }
+    }
+
+    #[test]
+    fn test_inspect() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        write!(
+            temp_file,
+            r#"(component
+                (import "example:log/sink" (instance
+                    (export "write" (func (param "msg" string)))))
+                (core module $m
+                    (func (export "add") (param i32 i32) (result i32)
+                        local.get 0
+                        local.get 1
+                        i32.add))
+                (core instance $i (instantiate $m))
+                (func $add (param "a" u32) (param "b" u32) (result u32)
+                    (canon lift (core func $i "add")))
+                (instance $api (export "add" (func $add)))
+                (export "example:math/api" (instance $api)))"#
+        )
+        .unwrap();
+
+        let inspection = inspect(&temp_file.path().to_path_buf()).unwrap();
+        assert_eq!(
+            inspection.exports,
+            vec![Item {
+                name: "example:math/api#add".into(),
+                kind: "func",
+                signature: "func(a: u32, b: u32) -> u32".into(),
+            }]
+        );
+        assert_eq!(
+            inspection.imports,
+            vec![Item {
+                name: "example:log/sink#write".into(),
+                kind: "func",
+                signature: "func(msg: string)".into(),
+            }]
+        );
+        assert_eq!(
+            inspection.to_string(),
+            "exports:\n  example:math/api#add: func(a: u32, b: u32) -> u32\n\
+             imports:\n  example:log/sink#write: func(msg: string)\n"
+        );
+    }
+
+    #[test]
+    fn test_inspect_file_not_found() {
+        let failure = inspect(&PathBuf::from("/nonexistent/file.wasm")).unwrap_err();
+        assert_eq!(failure.kind, ErrorKind::NotFound);
+    }
 }
@@ -1,6 +1,13 @@
 use std::path::PathBuf;
 use std::process::Command;
 
+/// Parse the envelope the CLI prints after the output of `cargo run`
+fn parse_envelope(output: &[u8]) -> serde_json::Value {
+    let output = String::from_utf8_lossy(output);
+    let start = output.find("\n{").map_or(0, |i| i + 1);
+    serde_json::from_str(&output[start..]).expect("envelope")
+}
+
 /// Integration test for basic CLI functionality
 #[test]
 fn test_cli_help() {
@@ -38,6 +45,137 @@ fn test_cli_file_not_found() {
     let stderr = String::from_utf8(output.stderr).unwrap();
     assert!(stderr.contains("\"success\": false"));
     assert!(stderr.contains("not found"));
+
+    assert_eq!(output.status.code(), Some(3));
+    let envelope = parse_envelope(stderr.as_bytes());
+    assert_eq!(envelope["kind"], "not-found");
+    assert!(envelope["context"].is_array());
+}
+
+/// `--engine all` reports the failure of every engine and that they agree
+#[test]
+fn test_cli_engine_all_file_not_found() {
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "-p",
+            "cli",
+            "--",
+            "-w",
+            "/nonexistent.wasm",
+            "-f",
+            "test",
+            "--engine",
+            "all",
+        ])
+        .output()
+        .expect("Failed to execute CLI");
+
+    assert_eq!(output.status.code(), Some(3));
+    let envelope = parse_envelope(&output.stderr);
+    assert_eq!(envelope["success"], false);
+    assert_eq!(envelope["differ"], false);
+    assert_eq!(envelope["engines"]["v21"]["kind"], "not-found");
+    assert_eq!(envelope["engines"]["v41"]["kind"], "not-found");
+}
+
+/// `--engine all` runs a golden function on v21 and v41 with identical results
+#[test]
+fn test_cli_engine_all_golden() {
+    let wasm = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../golden/out/argon2.wasm");
+    if !wasm.exists() {
+        println!("Skipping test: golden component not built");
+        return;
+    }
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "-p",
+            "cli",
+            "--",
+            "-w",
+            wasm.to_str().unwrap(),
+            "-f",
+            "sammyne:argon2/api@1.0.0#hash",
+            "--engine",
+            "all",
+            "[1,2,3]",
+            "\"hello-world-hello-world\"",
+        ])
+        .output()
+        .expect("Failed to execute CLI");
+
+    assert!(output.status.success());
+    let envelope = parse_envelope(&output.stdout);
+    assert_eq!(envelope["success"], true);
+    assert_eq!(envelope["differ"], false);
+    assert_eq!(
+        envelope["engines"]["v21"]["result"],
+        envelope["engines"]["v41"]["result"]
+    );
+}
+
+/// `inspect --json` lists the exported functions of a golden component
+/// under the names `-f` expects
+#[test]
+fn test_cli_inspect_golden() {
+    let wasm = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../golden/out/argon2.wasm");
+    if !wasm.exists() {
+        println!("Skipping test: golden component not built");
+        return;
+    }
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "-p",
+            "cli",
+            "--",
+            "inspect",
+            "-w",
+            wasm.to_str().unwrap(),
+            "--json",
+        ])
+        .output()
+        .expect("Failed to execute CLI");
+
+    assert!(output.status.success());
+    let envelope = parse_envelope(&output.stdout);
+    assert_eq!(envelope["success"], true);
+    let hash = envelope["exports"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|item| item["name"] == "sammyne:argon2/api@1.0.0#hash")
+        .expect("hash export");
+    assert_eq!(hash["kind"], "func");
+    assert_eq!(
+        hash["signature"],
+        "func(password: list<u8>, salt: string) -> result<list<u8>, string>"
+    );
+    assert!(envelope["imports"].is_array());
+}
+
+/// `inspect` reports a missing file like a run does
+#[test]
+fn test_cli_inspect_file_not_found() {
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "-p",
+            "cli",
+            "--",
+            "inspect",
+            "-w",
+            "/nonexistent.wasm",
+        ])
+        .output()
+        .expect("Failed to execute CLI");
+
+    assert_eq!(output.status.code(), Some(3));
+    let envelope = parse_envelope(&output.stderr);
+    assert_eq!(envelope["kind"], "not-found");
 }
 
 /// Integration test for JSON parameter parsing error
@@ -65,6 +203,50 @@ fn test_cli_invalid_json_param() {
         .expect("Failed to execute CLI");
 
     assert!(!output.status.success());
+    assert_eq!(output.status.code(), Some(4));
+    let envelope = parse_envelope(&output.stderr);
+    assert_eq!(envelope["success"], false);
+    assert_eq!(envelope["kind"], "parse");
+}
+
+/// A memory growing beyond `--max-memory` fails the call in the host, which
+/// isn't a trap of the guest
+#[test]
+fn test_cli_max_memory_exceeded() {
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+    let mut temp_file = NamedTempFile::new().unwrap();
+    write!(
+        temp_file,
+        r#"(component
+            (core module $m
+                (memory 1)
+                (func (export "run") (drop (memory.grow (i32.const 10)))))
+            (core instance $i (instantiate $m))
+            (func (export "run") (canon lift (core func $i "run"))))"#
+    )
+    .unwrap();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "-p",
+            "cli",
+            "--",
+            "-w",
+            temp_file.path().to_str().unwrap(),
+            "-f",
+            "run",
+            "--max-memory",
+            "65536",
+        ])
+        .output()
+        .expect("Failed to execute CLI");
+
+    assert_eq!(output.status.code(), Some(11));
+    let envelope = parse_envelope(&output.stderr);
+    assert_eq!(envelope["success"], false);
+    assert_eq!(envelope["kind"], "host");
 }
 
 /// Integration test with valid WASM component (if available)
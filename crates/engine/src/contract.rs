@@ -0,0 +1,141 @@
+//! Minimal pallet-contracts host running the ink! erc20 contract of
+//! wasmi-benchmarks
+//!
+//! `core_linker` of every version links the `seal0` / `__unstable__` imports
+//! of erc20 to a [`ContractHost`] held by the core store: `seal_input` hands
+//! out [`ContractHost::input`], storage lives in a map, events are dropped
+//! and `seal_return` ends the call with a [`ContractReturn`].
+
+use crate::EngineError;
+use anyhow::Context;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Account of the caller returned by `seal_caller`
+pub const CALLER: [u8; 32] = [1; 32];
+
+/// `seal_get_storage` return code of a missing key
+pub(crate) const KEY_NOT_FOUND: u32 = 3;
+
+/// `seal_set_storage` return value when the key had no value
+pub(crate) const SENTINEL: u32 = u32::MAX;
+
+/// State of a contract call: its input and the contract storage
+#[derive(Debug, Clone, Default)]
+pub struct ContractHost {
+    /// Input of the next call, e.g. a selector and its SCALE-encoded arguments
+    pub input: Vec<u8>,
+    storage: HashMap<Vec<u8>, Vec<u8>>,
+}
+
+impl ContractHost {
+    pub(crate) fn get_storage(&self, key: &[u8]) -> Option<&[u8]> {
+        self.storage.get(key).map(Vec::as_slice)
+    }
+
+    /// Store `value` under `key`, returning the size of the previous value or
+    /// [`SENTINEL`]
+    pub(crate) fn set_storage(&mut self, key: Vec<u8>, value: Vec<u8>) -> u32 {
+        match self.storage.insert(key, value) {
+            Some(previous) => previous.len() as u32,
+            None => SENTINEL,
+        }
+    }
+}
+
+/// Error `seal_return` ends a call with, which [`crate::Runtime::call_core`]
+/// treats as a normal return unless the contract reverted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractReturn {
+    /// Return flags, bit 0 being `REVERT`
+    pub flags: u32,
+    /// Output of the contract, e.g. the SCALE-encoded result of a message
+    pub data: Vec<u8>,
+}
+
+impl ContractReturn {
+    pub fn reverted(&self) -> bool {
+        self.flags & 1 != 0
+    }
+}
+
+impl fmt::Display for ContractReturn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "contract returned with flags {:#x}", self.flags)
+    }
+}
+
+impl std::error::Error for ContractReturn {}
+
+/// Export the memory of a contract as "memory"
+///
+/// pallet-contracts reaches the memory of a contract without it being
+/// exported, while wasmtime host functions can only reach exported memories.
+///
+/// # Errors
+/// If `wasm` isn't a module with an export section
+pub fn export_memory(wasm: &[u8]) -> Result<Vec<u8>, EngineError> {
+    const EXPORT_SECTION: u8 = 7;
+    const MEMORY: u8 = 2;
+
+    anyhow::ensure!(
+        wasm.len() >= 8 && wasm.starts_with(b"\0asm"),
+        "not a WASM module"
+    );
+    let mut patched = wasm[..8].to_vec();
+    let mut offset = 8;
+    let mut exported = false;
+    while offset < wasm.len() {
+        let id = wasm[offset];
+        let (size, start) = read_u32(wasm, offset + 1)?;
+        let end = start + size as usize;
+        anyhow::ensure!(end <= wasm.len(), "section {id} overflows the module");
+
+        if id == EXPORT_SECTION {
+            let (count, items) = read_u32(wasm, start)?;
+            let mut section = Vec::new();
+            write_u32(&mut section, count + 1);
+            section.extend_from_slice(&wasm[items..end]);
+            write_u32(&mut section, "memory".len() as u32);
+            section.extend_from_slice(b"memory");
+            section.extend_from_slice(&[MEMORY, 0]);
+
+            patched.push(EXPORT_SECTION);
+            write_u32(&mut patched, section.len() as u32);
+            patched.extend_from_slice(&section);
+            exported = true;
+        } else {
+            patched.extend_from_slice(&wasm[offset..end]);
+        }
+        offset = end;
+    }
+    anyhow::ensure!(exported, "the module has no export section");
+
+    Ok(patched)
+}
+
+/// Read a LEB128 `u32` at `offset`, returning it and the offset past it
+fn read_u32(bytes: &[u8], mut offset: usize) -> Result<(u32, usize), EngineError> {
+    let mut value = 0u32;
+    for shift in (0..35).step_by(7) {
+        let byte = *bytes.get(offset).context("truncated LEB128")?;
+        offset += 1;
+        value |= u32::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok((value, offset));
+        }
+    }
+    anyhow::bail!("LEB128 overflows u32")
+}
+
+fn write_u32(bytes: &mut Vec<u8>, mut value: u32) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            bytes.push(byte);
+            return;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
@@ -0,0 +1,72 @@
+use crate::Runtime;
+use std::fmt;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Interval between two epoch increments of an [`EpochTicker`], i.e. the
+/// granularity of timeouts
+pub const EPOCH_TICK: Duration = Duration::from_millis(10);
+
+/// Epoch deadline stores start with: never reached, yet small enough not to
+/// overflow once added to the current epoch
+pub(crate) const NO_DEADLINE: u64 = u64::MAX / 2;
+
+/// Error of a call or an instantiation interrupted by the deadline of
+/// [`Runtime::set_timeout`]
+///
+/// It's attached as context to the `Trap::Interrupt` of the call, so that
+/// `error.is::<Timeout>()` tells timeouts apart from other traps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timeout;
+
+impl fmt::Display for Timeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("call timed out")
+    }
+}
+
+/// Epoch ticks until a deadline `timeout` from now, rounded up
+pub(crate) fn timeout_ticks(timeout: Duration) -> u64 {
+    let ticks = timeout.as_nanos().div_ceil(EPOCH_TICK.as_nanos());
+    ticks.clamp(1, NO_DEADLINE as u128) as u64
+}
+
+/// Thread incrementing the epoch of an engine every [`EPOCH_TICK`], which
+/// stops when dropped
+///
+/// Deadlines set by [`Runtime::set_timeout`] only expire while a ticker of
+/// their engine runs.
+pub struct EpochTicker {
+    stop: Option<mpsc::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl EpochTicker {
+    /// Start ticking the epoch of `engine`, built with
+    /// `EngineOptions::epoch_interruption`
+    pub fn start<R: Runtime + 'static>(engine: &R::Engine) -> Self {
+        let engine = engine.clone();
+        let (stop, stopped) = mpsc::channel::<()>();
+        let thread = std::thread::spawn(move || {
+            while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(EPOCH_TICK) {
+                R::increment_epoch(&engine);
+            }
+        });
+
+        Self {
+            stop: Some(stop),
+            thread: Some(thread),
+        }
+    }
+}
+
+impl Drop for EpochTicker {
+    fn drop(&mut self) {
+        // disconnecting the channel wakes the thread up at once
+        drop(self.stop.take());
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+    }
+}
@@ -1,7 +1,32 @@
 /// Error type for engine operations
 pub type EngineError = anyhow::Error;
 
+// `bindgen!` of wasmtime v21 hardcodes the `wasmtime` crate name in its output
+extern crate wasmtime_v21 as wasmtime;
+
+pub mod contract;
+mod epoch;
+mod runtime;
+mod typed;
+mod value;
+mod version;
+mod wasi_options;
+
+pub use epoch::{EPOCH_TICK, EpochTicker, Timeout};
+pub use runtime::{Allocator, EngineOptions, OptLevel, PoolingLimits, Profile, Runtime, Strategy};
+pub use typed::TypedRuntime;
+pub use value::{Value, ValueType};
+pub use wasi_options::{CAPTURE_CAPACITY, Stdio, WasiOptions};
+
 pub mod v21;
+#[cfg(feature = "v25")]
+pub mod v25;
+#[cfg(feature = "v29")]
+pub mod v29;
+#[cfg(feature = "v33")]
+pub mod v33;
+#[cfg(feature = "v37")]
+pub mod v37;
 pub mod v41;
 
 #[cfg(test)]
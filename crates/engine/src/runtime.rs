@@ -0,0 +1,454 @@
+use crate::{EngineError, Value, ValueType, WasiOptions};
+use std::future::Future;
+use std::path::Path;
+use std::time::Duration;
+
+/// Options applied when building an engine for any wasmtime version
+#[derive(Debug, Clone)]
+pub struct EngineOptions {
+    /// Enable `Config::async_support`, required by the `*_async` calls
+    pub async_support: bool,
+    /// Compile functions on multiple threads, on by default like wasmtime
+    pub parallel_compilation: bool,
+    /// Instance allocation strategy
+    pub allocator: Allocator,
+    /// Compiler settings
+    pub profile: Profile,
+    /// Canonicalise NaNs and make relaxed SIMD deterministic, the engine
+    /// half of the deterministic mode, see [`WasiOptions::deterministic`]
+    pub deterministic: bool,
+    /// Enable `Config::consume_fuel`, stores then start with `u64::MAX` fuel
+    /// and [`Runtime::fuel`] tells what is left
+    pub consume_fuel: bool,
+    /// Enable `Config::epoch_interruption`, for the timeouts of
+    /// [`Runtime::set_timeout`]
+    pub epoch_interruption: bool,
+}
+
+impl Default for EngineOptions {
+    fn default() -> Self {
+        Self {
+            async_support: false,
+            parallel_compilation: true,
+            allocator: Allocator::default(),
+            profile: Profile::default(),
+            deterministic: false,
+            consume_fuel: false,
+            epoch_interruption: false,
+        }
+    }
+}
+
+impl EngineOptions {
+    /// Suffix appended to benchmark names, empty for the default profile and
+    /// allocator
+    ///
+    /// `async_support` and `parallel_compilation` aren't part of it, since
+    /// the benchmarks using them already carry them in their names.
+    pub fn suffix(&self) -> String {
+        let mut suffix = String::new();
+        if self.profile != Profile::default() {
+            suffix.push('_');
+            suffix.push_str(&self.profile.label());
+        }
+        if let Allocator::Pooling(_) = self.allocator {
+            suffix.push_str("_pooling");
+        }
+        if self.deterministic {
+            suffix.push_str("_deterministic");
+        }
+        if self.consume_fuel {
+            suffix.push_str("_fuel");
+        }
+        if self.epoch_interruption {
+            suffix.push_str("_epoch");
+        }
+        suffix
+    }
+}
+
+/// Cranelift optimization level, i.e. `OptLevel`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OptLevel {
+    /// No optimizations
+    None,
+    /// Optimize for speed, the wasmtime default
+    #[default]
+    Speed,
+    /// Optimize for speed and code size
+    SpeedAndSize,
+}
+
+/// Compiler backend, i.e. `Strategy`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Strategy {
+    /// The optimizing compiler, the wasmtime default
+    #[default]
+    Cranelift,
+    /// The baseline compiler, see [`Runtime::SUPPORTS_WINCH`]
+    Winch,
+}
+
+/// Compiler settings an engine is built with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Profile {
+    /// `Config::cranelift_opt_level`, ignored by Winch
+    pub opt_level: OptLevel,
+    /// `Config::strategy`
+    pub strategy: Strategy,
+    /// `Config::wasm_simd`, relaxed SIMD follows it
+    pub simd: bool,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            opt_level: OptLevel::default(),
+            strategy: Strategy::default(),
+            simd: true,
+        }
+    }
+}
+
+impl Profile {
+    /// Profiles the benchmarks iterate over: the default one, then each knob
+    /// changed on its own
+    pub fn matrix() -> Vec<Profile> {
+        let default = Profile::default();
+        vec![
+            default,
+            Profile {
+                opt_level: OptLevel::None,
+                ..default
+            },
+            Profile {
+                opt_level: OptLevel::SpeedAndSize,
+                ..default
+            },
+            Profile {
+                simd: false,
+                ..default
+            },
+            Profile {
+                strategy: Strategy::Winch,
+                ..default
+            },
+        ]
+    }
+
+    /// Whether runtime `R` can build an engine with this profile
+    pub fn supported_by<R: Runtime>(&self) -> bool {
+        self.strategy == Strategy::Cranelift || R::SUPPORTS_WINCH
+    }
+
+    /// Label used in benchmark names, e.g. "cranelift-speed-simd" or
+    /// "winch-nosimd"
+    pub fn label(&self) -> String {
+        let compiler = match (self.strategy, self.opt_level) {
+            (Strategy::Winch, _) => "winch",
+            (Strategy::Cranelift, OptLevel::None) => "cranelift-none",
+            (Strategy::Cranelift, OptLevel::Speed) => "cranelift-speed",
+            (Strategy::Cranelift, OptLevel::SpeedAndSize) => "cranelift-speedandsize",
+        };
+        let simd = if self.simd { "simd" } else { "nosimd" };
+        format!("{compiler}-{simd}")
+    }
+}
+
+/// Instance allocation strategy, i.e. `InstanceAllocationStrategy`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Allocator {
+    /// Allocate memories and tables on demand, the wasmtime default
+    #[default]
+    OnDemand,
+    /// Preallocate slots in a pool bounded by the given limits
+    Pooling(PoolingLimits),
+}
+
+/// Limits of the pooling instance allocator, i.e. `PoolingAllocationConfig`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoolingLimits {
+    /// Maximum number of concurrently live component instances
+    pub total_component_instances: u32,
+    /// Maximum number of concurrently live core instances
+    pub total_core_instances: u32,
+    /// Maximum number of concurrently live linear memories
+    pub total_memories: u32,
+    /// Maximum number of concurrently live tables
+    pub total_tables: u32,
+    /// Maximum number of core instances within one component
+    pub max_core_instances_per_component: u32,
+    /// Maximum number of linear memories within one component
+    pub max_memories_per_component: u32,
+    /// Maximum number of tables within one component
+    pub max_tables_per_component: u32,
+    /// Maximum size of one linear memory in bytes
+    pub max_memory_size: usize,
+    /// Maximum number of elements of one table
+    pub table_elements: usize,
+}
+
+impl Default for PoolingLimits {
+    fn default() -> Self {
+        Self {
+            total_component_instances: 100,
+            total_core_instances: 1000,
+            total_memories: 100,
+            total_tables: 1000,
+            max_core_instances_per_component: 50,
+            max_memories_per_component: 10,
+            max_tables_per_component: 50,
+            max_memory_size: 64 << 20,
+            table_elements: 20_000,
+        }
+    }
+}
+
+/// Version-agnostic view over one wasmtime release
+///
+/// Every `engine::vXX` module implements this trait on a marker type (e.g.
+/// [`crate::v21::V21`]), so a benchmark body written once against
+/// `R: Runtime` runs on every backend.
+///
+/// # Example
+/// ```ignore
+/// fn call<R: Runtime>(path: &Path, name: &str, params: &[R::Val]) -> Result<()> {
+///     let options = EngineOptions::default();
+///     let engine = R::new_engine(&options)?;
+///     let component = R::load_component(&engine, path)?;
+///     let pre = R::instantiate_pre(&engine, &component, &options)?;
+///
+///     let mut store = R::new_store(&engine);
+///     let instance = R::instantiate(&pre, &mut store)?;
+///     let func = R::find_func(&instance, &mut store, name)?;
+///
+///     let mut results = vec![R::Val::from(Value::Bool(false)); R::results_len(&func, &store)];
+///     R::call(&func, &mut store, params, &mut results)?;
+///     R::post_return(&func, &mut store)
+/// }
+/// ```
+pub trait Runtime {
+    /// Short label used in benchmark names, e.g. "v21"
+    const NAME: &'static str;
+    /// Whether engines can be built with [`Strategy::Winch`]
+    const SUPPORTS_WINCH: bool;
+
+    type Engine: Clone + Send + Sync + 'static;
+    type Module;
+    type Component;
+    type Linker;
+    type InstancePre: Clone + Send + Sync + 'static;
+    type Store: Send;
+    type Instance: Send;
+    type Func: Copy + Send + Sync;
+    /// Core WASM value, e.g. `wasmtime::Val`
+    type CoreVal: Clone + From<i32> + From<i64> + From<f32> + From<f64>;
+    type CoreInstancePre;
+    type CoreStore: Send;
+    type CoreInstance;
+    /// Component value, convertible from and to the version-neutral [`Value`]
+    type Val: Clone + Send + Sync + 'static + From<Value> + TryInto<Value, Error = EngineError>;
+
+    /// Full version string, e.g. "wasmtime-21.0"
+    fn version() -> &'static str;
+
+    /// Create an engine with component model support enabled
+    fn new_engine(options: &EngineOptions) -> Result<Self::Engine, EngineError>;
+
+    /// Compile a core WASM module from its binary form
+    fn load_module(engine: &Self::Engine, bytes: &[u8]) -> Result<Self::Module, EngineError>;
+
+    /// Instantiate a core module and run one of its exports
+    ///
+    /// `args` are checked against the export's signature and all results are
+    /// returned.
+    fn execute(
+        engine: &Self::Engine,
+        module: &Self::Module,
+        function_name: &str,
+        args: &[Self::CoreVal],
+    ) -> Result<Vec<Self::CoreVal>, EngineError>;
+
+    /// Link WASI preview1 plus the host imports of the wasmi-benchmarks
+    /// modules and pre-instantiate a core module
+    fn core_instance_pre(
+        engine: &Self::Engine,
+        module: &Self::Module,
+    ) -> Result<Self::CoreInstancePre, EngineError>;
+
+    /// Create a store for core modules, preopening `dir` as "." when given
+    fn new_core_store(
+        engine: &Self::Engine,
+        dir: Option<&Path>,
+    ) -> Result<Self::CoreStore, EngineError>;
+
+    fn instantiate_core(
+        pre: &Self::CoreInstancePre,
+        store: &mut Self::CoreStore,
+    ) -> Result<Self::CoreInstance, EngineError>;
+
+    /// Call an export of a core instance, see [`Runtime::execute`] for the
+    /// handling of `args` and results
+    fn call_core(
+        instance: &Self::CoreInstance,
+        store: &mut Self::CoreStore,
+        function_name: &str,
+        args: &[Self::CoreVal],
+    ) -> Result<Vec<Self::CoreVal>, EngineError>;
+
+    /// Set the input `seal_input` hands to the next contract call made with
+    /// `store`, see [`crate::contract`]
+    fn set_contract_input(store: &mut Self::CoreStore, input: Vec<u8>);
+
+    /// Read a numeric core value as `f64`, `None` for v128 and references
+    fn core_val_as_f64(val: &Self::CoreVal) -> Option<f64>;
+
+    /// Compile a WASM component from its binary form
+    fn compile_component(
+        engine: &Self::Engine,
+        bytes: &[u8],
+    ) -> Result<Self::Component, EngineError>;
+
+    /// Compile a WASM component from a file
+    fn load_component(engine: &Self::Engine, path: &Path) -> Result<Self::Component, EngineError>;
+
+    /// Compile a component into a precompiled `.cwasm` artifact for `engine`
+    fn precompile_component(engine: &Self::Engine, bytes: &[u8]) -> Result<Vec<u8>, EngineError>;
+
+    /// Serialize an already compiled component into a `.cwasm` artifact
+    fn serialize_component(component: &Self::Component) -> Result<Vec<u8>, EngineError>;
+
+    /// Load a component from a precompiled artifact in memory
+    ///
+    /// # Safety
+    /// `bytes` must come from [`Runtime::precompile_component`] or
+    /// [`Runtime::serialize_component`] of the same wasmtime version, see
+    /// `Component::deserialize`.
+    unsafe fn deserialize_component(
+        engine: &Self::Engine,
+        bytes: &[u8],
+    ) -> Result<Self::Component, EngineError>;
+
+    /// Load a component from a precompiled artifact file, which is mmap-ed
+    ///
+    /// # Safety
+    /// Same as [`Runtime::deserialize_component`], and the file must not be
+    /// modified while the component is alive.
+    unsafe fn deserialize_component_file(
+        engine: &Self::Engine,
+        path: &Path,
+    ) -> Result<Self::Component, EngineError>;
+
+    /// Create a component linker with WASI preview2 added
+    ///
+    /// The async or sync flavour of WASI is picked from `options`, which must
+    /// match the ones the engine was created with.
+    fn new_linker(
+        engine: &Self::Engine,
+        options: &EngineOptions,
+    ) -> Result<Self::Linker, EngineError>;
+
+    /// Pre-instantiate the component against the imports of `linker`
+    fn link_instantiate_pre(
+        linker: &Self::Linker,
+        component: &Self::Component,
+    ) -> Result<Self::InstancePre, EngineError>;
+
+    /// Link WASI preview2 and pre-instantiate the component
+    ///
+    /// Shorthand for [`Runtime::new_linker`] followed by
+    /// [`Runtime::link_instantiate_pre`].
+    fn instantiate_pre(
+        engine: &Self::Engine,
+        component: &Self::Component,
+        options: &EngineOptions,
+    ) -> Result<Self::InstancePre, EngineError> {
+        let linker = Self::new_linker(engine, options)?;
+        Self::link_instantiate_pre(&linker, component)
+    }
+
+    /// Create a store holding a fresh WASI state
+    fn new_store(engine: &Self::Engine) -> Self::Store;
+
+    /// Create a store holding a WASI state built from `options`
+    ///
+    /// Fails when a preopened directory can't be opened.
+    fn new_store_with(
+        engine: &Self::Engine,
+        options: &WasiOptions,
+    ) -> Result<Self::Store, EngineError>;
+
+    /// Output captured in a store created with `Stdio::Capture`, see
+    /// `WasiP2State::captured_stdout`
+    fn captured_stdout(store: &Self::Store) -> Option<Vec<u8>>;
+
+    fn captured_stderr(store: &Self::Store) -> Option<Vec<u8>>;
+
+    /// Fuel left in the store, `None` unless the engine was built with
+    /// `EngineOptions::consume_fuel`
+    ///
+    /// The fuel a call consumed is the difference before and after it.
+    fn fuel(store: &Self::Store) -> Option<u64>;
+
+    /// Increment the epoch of `engine`, see [`crate::EpochTicker`]
+    fn increment_epoch(engine: &Self::Engine);
+
+    /// Interrupt instantiations and calls in `store` once `timeout` has
+    /// passed from now, with a [`crate::Timeout`] error
+    ///
+    /// The engine needs `EngineOptions::epoch_interruption` and a running
+    /// [`crate::EpochTicker`]. Stores have no deadline until this is called.
+    fn set_timeout(store: &mut Self::Store, timeout: Duration);
+
+    fn instantiate(
+        pre: &Self::InstancePre,
+        store: &mut Self::Store,
+    ) -> Result<Self::Instance, EngineError>;
+
+    fn instantiate_async(
+        pre: &Self::InstancePre,
+        store: &mut Self::Store,
+    ) -> impl Future<Output = Result<Self::Instance, EngineError>> + Send;
+
+    /// Find a function by name in form of "interface#function"
+    fn find_func(
+        instance: &Self::Instance,
+        store: &mut Self::Store,
+        name: &str,
+    ) -> Result<Self::Func, EngineError>;
+
+    /// Number of results returned by `func`, used to size the results buffer
+    fn results_len(func: &Self::Func, store: &Self::Store) -> usize;
+
+    /// Types of the parameters of `func`
+    fn param_types(func: &Self::Func, store: &Self::Store) -> Vec<ValueType>;
+
+    /// Types of the results of `func`
+    fn result_types(func: &Self::Func, store: &Self::Store) -> Vec<ValueType>;
+
+    fn call(
+        func: &Self::Func,
+        store: &mut Self::Store,
+        params: &[Self::Val],
+        results: &mut [Self::Val],
+    ) -> Result<(), EngineError>;
+
+    fn call_async(
+        func: &Self::Func,
+        store: &mut Self::Store,
+        params: &[Self::Val],
+        results: &mut [Self::Val],
+    ) -> impl Future<Output = Result<(), EngineError>> + Send;
+
+    fn post_return(func: &Self::Func, store: &mut Self::Store) -> Result<(), EngineError>;
+
+    fn post_return_async(
+        func: &Self::Func,
+        store: &mut Self::Store,
+    ) -> impl Future<Output = Result<(), EngineError>> + Send;
+
+    /// Whether `error` of a call is a trap of the guest, e.g. `unreachable`
+    ///
+    /// Errors of host functions, like a store limit being hit, are not.
+    fn is_trap(error: &EngineError) -> bool;
+}
@@ -27,3 +27,209 @@ fn test_v21_wasi_reexport() {
     // is properly re-exported from wasmtime-wasi-v21
     let _ = "wasi module is accessible through engine::v21::wasi";
 }
+
+#[test]
+fn test_value_into_val() {
+    use crate::v21::component::Val as ValV21;
+    use crate::v41::component::Val as ValV41;
+
+    let value = Value::Result(Ok(Some(Box::new(Value::bytes([1, 2])))));
+
+    assert_eq!(
+        ValV21::from(value.clone()),
+        ValV21::Result(Ok(Some(Box::new(ValV21::List(vec![
+            ValV21::U8(1),
+            ValV21::U8(2)
+        ])))))
+    );
+    assert_eq!(
+        ValV41::from(value),
+        ValV41::Result(Ok(Some(Box::new(ValV41::List(vec![
+            ValV41::U8(1),
+            ValV41::U8(2)
+        ])))))
+    );
+}
+
+#[test]
+fn test_val_try_into_value() {
+    use crate::v21::component::Val as ValV21;
+    use crate::v41::component::Val as ValV41;
+
+    let value = Value::Record(vec![
+        ("name".to_string(), Value::from("x")),
+        (
+            "size".to_string(),
+            Value::Option(Some(Box::new(Value::U32(1)))),
+        ),
+        (
+            "shape".to_string(),
+            Value::Variant("empty".to_string(), None),
+        ),
+    ]);
+
+    let v21: Value = ValV21::from(value.clone()).try_into().unwrap();
+    assert_eq!(v21, value);
+    let v41: Value = ValV41::from(value.clone()).try_into().unwrap();
+    assert_eq!(v41, value);
+}
+
+#[test]
+fn test_value_type_from_type() {
+    const WAT: &str = r#"(component
+        (type $t (record (field "data" (list u8)) (field "shape" (option (result s32)))))
+        (export "t" (type $t))
+    )"#;
+
+    let expected = ValueType::Record(vec![
+        ("data".to_string(), ValueType::List(Box::new(ValueType::U8))),
+        (
+            "shape".to_string(),
+            ValueType::Option(Box::new(ValueType::Result {
+                ok: Some(Box::new(ValueType::S32)),
+                err: None,
+            })),
+        ),
+    ]);
+
+    {
+        use crate::v21::component::{Component, types::ComponentItem};
+
+        let engine = crate::v21::Engine::default();
+        let component = Component::new(&engine, WAT).unwrap();
+        match component.component_type().get_export(&engine, "t") {
+            Some(ComponentItem::Type(ty)) => assert_eq!(ValueType::from(ty), expected),
+            _ => panic!("Expected type export"),
+        }
+    }
+
+    {
+        use crate::v41::component::{Component, types::ComponentItem};
+
+        let engine = crate::v41::Engine::default();
+        let component = Component::new(&engine, WAT).unwrap();
+        match component.component_type().get_export(&engine, "t") {
+            Some(ComponentItem::Type(ty)) => assert_eq!(ValueType::from(ty), expected),
+            _ => panic!("Expected type export"),
+        }
+    }
+}
+
+#[test]
+fn test_profile_matrix_engines() {
+    use crate::v21::V21;
+    use crate::v41::V41;
+
+    for profile in Profile::matrix() {
+        let options = EngineOptions {
+            profile,
+            ..Default::default()
+        };
+        assert_eq!(
+            V21::new_engine(&options).is_ok(),
+            profile.supported_by::<V21>()
+        );
+        assert!(V41::new_engine(&options).is_ok(), "{}", profile.label());
+    }
+}
+
+#[test]
+fn test_engine_options_suffix() {
+    assert_eq!(EngineOptions::default().suffix(), "");
+
+    let options = EngineOptions {
+        profile: Profile {
+            strategy: Strategy::Winch,
+            simd: false,
+            ..Default::default()
+        },
+        allocator: Allocator::Pooling(PoolingLimits::default()),
+        ..Default::default()
+    };
+    assert_eq!(options.suffix(), "_winch-nosimd_pooling");
+
+    let options = EngineOptions {
+        deterministic: true,
+        ..options
+    };
+    assert_eq!(options.suffix(), "_winch-nosimd_pooling_deterministic");
+}
+
+#[test]
+fn test_timeout_ticks() {
+    use crate::epoch::timeout_ticks;
+    use std::time::Duration;
+
+    assert_eq!(timeout_ticks(Duration::ZERO), 1);
+    assert_eq!(timeout_ticks(EPOCH_TICK), 1);
+    assert_eq!(timeout_ticks(EPOCH_TICK + Duration::from_nanos(1)), 2);
+    assert_eq!(timeout_ticks(Duration::MAX), u64::MAX / 2);
+}
+
+#[test]
+fn test_new_store_with_wasi_options() {
+    use crate::v21::V21;
+    use crate::v41::V41;
+
+    let dir = env!("CARGO_MANIFEST_DIR");
+    let options = WasiOptions::new()
+        .arg("main")
+        .env("KEY", "VALUE")
+        .preopened_dir(dir, "/data", true)
+        .fixed_clock(std::time::Duration::from_secs(1))
+        .random_seed(42);
+
+    let engine_v21 = V21::new_engine(&EngineOptions::default()).unwrap();
+    let engine_v41 = V41::new_engine(&EngineOptions::default()).unwrap();
+    assert!(V21::new_store_with(&engine_v21, &options).is_ok());
+    assert!(V41::new_store_with(&engine_v41, &options).is_ok());
+
+    let missing = WasiOptions::new().preopened_dir("/no/such/dir", "/data", true);
+    assert!(V21::new_store_with(&engine_v21, &missing).is_err());
+    assert!(V41::new_store_with(&engine_v41, &missing).is_err());
+}
+
+#[test]
+fn test_contract_export_memory() {
+    use crate::v41::{Engine, Module};
+
+    // (module (memory 1) (func (export "f")))
+    let wasm = [
+        0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // header
+        0x01, 0x04, 0x01, 0x60, 0x00, 0x00, // type
+        0x03, 0x02, 0x01, 0x00, // function
+        0x05, 0x03, 0x01, 0x00, 0x01, // memory
+        0x07, 0x05, 0x01, 0x01, b'f', 0x00, 0x00, // export
+        0x0a, 0x04, 0x01, 0x02, 0x00, 0x0b, // code
+    ];
+    let patched = contract::export_memory(&wasm).unwrap();
+
+    let module = Module::new(&Engine::default(), &patched).unwrap();
+    let exports: Vec<_> = module.exports().map(|export| export.name()).collect();
+    assert_eq!(exports, ["f", "memory"]);
+
+    assert!(contract::export_memory(&wasm[..8]).is_err());
+    assert!(contract::export_memory(b"not wasm").is_err());
+}
+
+#[test]
+fn test_table_limit_overflow() {
+    use crate::v21::V21;
+    use crate::v41::V41;
+
+    // Table sizes are `u32` in v21
+    let pooling = EngineOptions {
+        allocator: Allocator::Pooling(PoolingLimits {
+            table_elements: usize::MAX,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    assert!(V21::new_engine(&pooling).is_err());
+
+    let engine_v21 = V21::new_engine(&EngineOptions::default()).unwrap();
+    let engine_v41 = V41::new_engine(&EngineOptions::default()).unwrap();
+    let limited = WasiOptions::new().max_table_elements(usize::MAX);
+    assert!(V21::new_store_with(&engine_v21, &limited).is_err());
+    assert!(V41::new_store_with(&engine_v41, &limited).is_ok());
+}
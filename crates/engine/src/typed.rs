@@ -0,0 +1,74 @@
+use crate::{EngineError, Runtime};
+
+/// Typed calls of the golden components through host bindings generated by
+/// `bindgen!` from their `wit/world.wit`
+///
+/// Each world is looked up once per instance, then every call goes through
+/// `TypedFunc` and runs `post_return` itself, unlike the dynamic
+/// [`Runtime::call`] which lifts and lowers `Val`s.
+pub trait TypedRuntime: Runtime {
+    /// Exports of argon2-world
+    type Argon2: Send;
+    /// Exports of pulldown-cmark-world
+    type PulldownCmark: Send;
+    /// Exports of the sevenz-7z world
+    type Sevenz7z: Send;
+    /// Exports of the rust-python world
+    type RustPython: Send;
+
+    fn argon2(
+        instance: &Self::Instance,
+        store: &mut Self::Store,
+    ) -> Result<Self::Argon2, EngineError>;
+
+    /// `sammyne:argon2/api#hash`
+    fn argon2_hash(
+        world: &Self::Argon2,
+        store: &mut Self::Store,
+        password: &[u8],
+        salt: &str,
+    ) -> Result<Result<Vec<u8>, String>, EngineError>;
+
+    fn pulldown_cmark(
+        instance: &Self::Instance,
+        store: &mut Self::Store,
+    ) -> Result<Self::PulldownCmark, EngineError>;
+
+    /// `sammyne:pulldown-cmark/api#parse`
+    fn pulldown_cmark_parse(
+        world: &Self::PulldownCmark,
+        store: &mut Self::Store,
+        markdown: &str,
+    ) -> Result<Result<String, String>, EngineError>;
+
+    fn sevenz_7z(
+        instance: &Self::Instance,
+        store: &mut Self::Store,
+    ) -> Result<Self::Sevenz7z, EngineError>;
+
+    /// `sammyne:sevenz7z/api#zip`
+    fn sevenz_7z_zip(
+        world: &Self::Sevenz7z,
+        store: &mut Self::Store,
+        req: &[u8],
+    ) -> Result<Result<Vec<u8>, String>, EngineError>;
+
+    /// `sammyne:sevenz7z/api#unzip`
+    fn sevenz_7z_unzip(
+        world: &Self::Sevenz7z,
+        store: &mut Self::Store,
+        req: &[u8],
+    ) -> Result<Result<Vec<u8>, String>, EngineError>;
+
+    fn rust_python(
+        instance: &Self::Instance,
+        store: &mut Self::Store,
+    ) -> Result<Self::RustPython, EngineError>;
+
+    /// `sammyne:rust-python/api#run`
+    fn rust_python_run(
+        world: &Self::RustPython,
+        store: &mut Self::Store,
+        code: &str,
+    ) -> Result<Result<String, String>, EngineError>;
+}
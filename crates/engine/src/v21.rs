@@ -1,65 +1,22 @@
-use crate::EngineError;
-use anyhow::Context;
+//! Engine on wasmtime v21, the code shared with the other versions being
+//! expanded by `crate::version::impl_version!`
 
-// Re-export wasmtime v21 types for convenience
-pub use wasmtime_v21::*;
+use wasmtime_wasi_v21::pipe::MemoryOutputPipe;
+use wasmtime_wasi_v21::preview1::{self as wasip1, WasiP1Ctx};
+use wasmtime_wasi_v21::{ResourceTable, WasiCtx, WasiCtxBuilder, WasiView};
+// preview2 lives at the root of wasmtime-wasi before v33
+use wasmtime_wasi_v21 as wasip2;
 
-use wasmtime_wasi_v21::{ResourceTable, WasiCtx, WasiView};
-// Re-export wasmtime-wasi v21 as wasi for convenience
-// Note: We re-export the module contents to allow easier access
-pub use wasmtime_wasi_v21 as wasi;
+pub mod bindings;
 
-/// Execute a function from the loaded WASM module
-///
-/// # Arguments
-/// * `engine` - The engine instance
-/// * `module` - The loaded WASM module
-/// * `function_name` - The name of the function to execute
-/// * `_args` - Arguments to pass to the function (currently unused)
-///
-/// # Returns
-/// The result of the function execution
-pub fn execute(
-    engine: &Engine,
-    module: &Module,
-    function_name: &str,
-    _args: &[u8],
-) -> Result<Vec<u8>, EngineError> {
-    use wasmtime_v21::{Instance, Store};
-    let mut store = Store::new(engine, ());
-    let instance = Instance::new(&mut store, module, &[]).context("无法实例化模块")?;
-
-    let func = instance
-        .get_typed_func::<(), i32>(&mut store, function_name)
-        .with_context(|| format!("无法找到 '{}' 函数或签名不匹配", function_name))?;
-
-    let result = func
-        .call(&mut store, ())
-        .with_context(|| format!("函数 '{}' 调用失败", function_name))?;
-
-    Ok(result.to_le_bytes().to_vec())
-}
-
-/// Get the version information of the engine
-///
-/// # Returns
-/// The version string "wasmtime-21.0"
-pub fn version() -> &'static str {
-    "wasmtime-21.0"
-}
-
-pub struct WasiP2State {
-    ctx: WasiCtx,
-    table: ResourceTable,
-}
-
-impl Default for WasiP2State {
-    fn default() -> Self {
-        Self {
-            ctx: wasi::WasiCtxBuilder::new().build(),
-            table: ResourceTable::new(),
-        }
-    }
+crate::version::impl_version! {
+    runtime: V21,
+    name: "v21",
+    version: "wasmtime-21.0",
+    wasmtime: wasmtime_v21,
+    wasi: wasmtime_wasi_v21,
+    // Winch in v21 only covers a subset of core wasm, too little for the golden components
+    supports_winch: false,
 }
 
 impl WasiView for WasiP2State {
@@ -72,29 +29,13 @@ impl WasiView for WasiP2State {
     }
 }
 
-/// Find a function in a component instance by name (supports "interface#function" format)
-///
-/// # Arguments
-/// * `instance` - The component instance
-/// * `store` - The store context
-/// * `name` - The function name in format "interface#function"
-///
-/// # Returns
-/// The function if found, otherwise an error
-///
-/// # Example
-/// ```ignore
-/// let func = find_func(&instance, &mut store, "sammyne:argon2/api#hash")?;
-/// ```
-pub fn find_func<T>(
+/// Look up the function `func_name` exported by the instance `interface`
+fn export_func<T>(
     instance: &component::Instance,
     store: &mut Store<T>,
-    name: &str,
+    interface: &str,
+    func_name: &str,
 ) -> Result<component::Func> {
-    let (interface, func_name) = name
-        .split_once('#')
-        .ok_or_else(|| anyhow::anyhow!("must in form of 'interface#func'"))?;
-
     let mut exports = instance.exports(&mut *store);
 
     let mut i = exports
@@ -104,3 +45,39 @@ pub fn find_func<T>(
     i.func(func_name)
         .with_context(|| format!("miss func '{func_name}'"))
 }
+
+/// Number of results of `func`
+fn results_len(func: &component::Func, store: &Store<WasiP2State>) -> usize {
+    func.results(store).len()
+}
+
+/// Version-neutral parameter types of `func`
+fn param_types(func: &component::Func, store: &Store<WasiP2State>) -> Vec<ValueType> {
+    func.params(store)
+        .iter()
+        .cloned()
+        .map(ValueType::from)
+        .collect()
+}
+
+/// Version-neutral result types of `func`
+fn result_types(func: &component::Func, store: &Store<WasiP2State>) -> Vec<ValueType> {
+    func.results(store)
+        .iter()
+        .cloned()
+        .map(ValueType::from)
+        .collect()
+}
+
+/// Linear memory limit of the pooling allocator, counted in 64 KiB pages in
+/// this version
+fn max_memory_size(config: &mut PoolingAllocationConfig, bytes: usize) {
+    config.memory_pages((bytes / 65536) as u64);
+}
+
+/// Table size limit, a `u32` in this version
+fn table_elements(limit: usize) -> Result<u32> {
+    u32::try_from(limit).with_context(|| format!("table limit {limit} exceeds u32::MAX"))
+}
+
+crate::value::impl_from_type!(component::Type);
@@ -0,0 +1,114 @@
+//! Host bindings of the golden components generated by `bindgen!`
+//!
+//! The v21 macro has no `wasmtime_crate` option, its output resolves
+//! `wasmtime` through the alias in the crate root.
+use super::{V21, WasiP2State};
+use crate::EngineError;
+use wasmtime_v21::Store;
+use wasmtime_v21::component::Instance;
+
+pub mod argon2 {
+    wasmtime_v21::component::bindgen!({
+        path: "../golden/argon2/wit",
+        world: "argon2-world",
+    });
+}
+
+pub mod pulldown_cmark {
+    wasmtime_v21::component::bindgen!({
+        path: "../golden/pulldown-cmark/wit",
+        world: "pulldown-cmark-world",
+    });
+}
+
+pub mod sevenz_7z {
+    wasmtime_v21::component::bindgen!({
+        path: "../golden/sevenz-7z/wit",
+        world: "helloworld",
+    });
+}
+
+pub mod rust_python {
+    wasmtime_v21::component::bindgen!({
+        path: "../golden/rust-python/wit",
+        world: "rust-python",
+    });
+}
+
+impl crate::TypedRuntime for V21 {
+    type Argon2 = argon2::Argon2World;
+    type PulldownCmark = pulldown_cmark::PulldownCmarkWorld;
+    type Sevenz7z = sevenz_7z::Helloworld;
+    type RustPython = rust_python::RustPython;
+
+    fn argon2(
+        instance: &Instance,
+        store: &mut Store<WasiP2State>,
+    ) -> Result<argon2::Argon2World, EngineError> {
+        argon2::Argon2World::new(store, instance)
+    }
+
+    fn argon2_hash(
+        world: &argon2::Argon2World,
+        store: &mut Store<WasiP2State>,
+        password: &[u8],
+        salt: &str,
+    ) -> Result<Result<Vec<u8>, String>, EngineError> {
+        world.sammyne_argon2_api().call_hash(store, password, salt)
+    }
+
+    fn pulldown_cmark(
+        instance: &Instance,
+        store: &mut Store<WasiP2State>,
+    ) -> Result<pulldown_cmark::PulldownCmarkWorld, EngineError> {
+        pulldown_cmark::PulldownCmarkWorld::new(store, instance)
+    }
+
+    fn pulldown_cmark_parse(
+        world: &pulldown_cmark::PulldownCmarkWorld,
+        store: &mut Store<WasiP2State>,
+        markdown: &str,
+    ) -> Result<Result<String, String>, EngineError> {
+        world
+            .sammyne_pulldown_cmark_api()
+            .call_parse(store, markdown)
+    }
+
+    fn sevenz_7z(
+        instance: &Instance,
+        store: &mut Store<WasiP2State>,
+    ) -> Result<sevenz_7z::Helloworld, EngineError> {
+        sevenz_7z::Helloworld::new(store, instance)
+    }
+
+    fn sevenz_7z_zip(
+        world: &sevenz_7z::Helloworld,
+        store: &mut Store<WasiP2State>,
+        req: &[u8],
+    ) -> Result<Result<Vec<u8>, String>, EngineError> {
+        world.sammyne_sevenz7z_api().call_zip(store, req)
+    }
+
+    fn sevenz_7z_unzip(
+        world: &sevenz_7z::Helloworld,
+        store: &mut Store<WasiP2State>,
+        req: &[u8],
+    ) -> Result<Result<Vec<u8>, String>, EngineError> {
+        world.sammyne_sevenz7z_api().call_unzip(store, req)
+    }
+
+    fn rust_python(
+        instance: &Instance,
+        store: &mut Store<WasiP2State>,
+    ) -> Result<rust_python::RustPython, EngineError> {
+        rust_python::RustPython::new(store, instance)
+    }
+
+    fn rust_python_run(
+        world: &rust_python::RustPython,
+        store: &mut Store<WasiP2State>,
+        code: &str,
+    ) -> Result<Result<String, String>, EngineError> {
+        world.sammyne_rust_python_api().call_run(store, code)
+    }
+}
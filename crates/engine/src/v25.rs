@@ -0,0 +1,83 @@
+//! Engine on wasmtime v25, the code shared with the other versions being
+//! expanded by `crate::version::impl_version!`
+
+use wasmtime_wasi_v25::pipe::MemoryOutputPipe;
+use wasmtime_wasi_v25::preview1::{self as wasip1, WasiP1Ctx};
+use wasmtime_wasi_v25::{ResourceTable, WasiCtx, WasiCtxBuilder, WasiView};
+// preview2 lives at the root of wasmtime-wasi before v33
+use wasmtime_wasi_v25 as wasip2;
+
+crate::version::impl_version! {
+    runtime: V25,
+    name: "v25",
+    version: "wasmtime-25.0",
+    wasmtime: wasmtime_v25,
+    wasi: wasmtime_wasi_v25,
+    // wasmtime is built without its `winch` feature for this version
+    supports_winch: false,
+}
+
+impl WasiView for WasiP2State {
+    fn ctx(&mut self) -> &mut WasiCtx {
+        &mut self.ctx
+    }
+
+    fn table(&mut self) -> &mut ResourceTable {
+        &mut self.table
+    }
+}
+
+/// Look up the function `func_name` exported by the instance `interface`
+fn export_func<T>(
+    instance: &component::Instance,
+    store: &mut Store<T>,
+    interface: &str,
+    func_name: &str,
+) -> Result<component::Func> {
+    let ii = instance
+        .get_export(&mut *store, None, interface)
+        .ok_or_else(|| anyhow::anyhow!("miss interface: {interface}"))?;
+
+    let fi = instance
+        .get_export(&mut *store, Some(&ii), func_name)
+        .with_context(|| format!("miss export-index for func '{func_name}'"))?;
+
+    instance
+        .get_func(&mut *store, fi)
+        .with_context(|| format!("miss func '{func_name}'"))
+}
+
+/// Number of results of `func`
+fn results_len(func: &component::Func, store: &Store<WasiP2State>) -> usize {
+    func.results(store).len()
+}
+
+/// Version-neutral parameter types of `func`
+fn param_types(func: &component::Func, store: &Store<WasiP2State>) -> Vec<ValueType> {
+    func.params(store)
+        .iter()
+        .cloned()
+        .map(ValueType::from)
+        .collect()
+}
+
+/// Version-neutral result types of `func`
+fn result_types(func: &component::Func, store: &Store<WasiP2State>) -> Vec<ValueType> {
+    func.results(store)
+        .iter()
+        .cloned()
+        .map(ValueType::from)
+        .collect()
+}
+
+/// Linear memory limit of the pooling allocator
+fn max_memory_size(config: &mut PoolingAllocationConfig, bytes: usize) {
+    config.max_memory_size(bytes);
+}
+
+/// Table size limit, a `u32` in this version
+fn table_elements(limit: usize) -> Result<u32> {
+    u32::try_from(limit).with_context(|| format!("table limit {limit} exceeds u32::MAX"))
+}
+
+crate::value::impl_from_type!(component::Type);
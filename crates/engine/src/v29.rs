@@ -0,0 +1,82 @@
+//! Engine on wasmtime v29, the code shared with the other versions being
+//! expanded by `crate::version::impl_version!`
+
+use wasmtime_wasi_v29::pipe::MemoryOutputPipe;
+use wasmtime_wasi_v29::preview1::{self as wasip1, WasiP1Ctx};
+use wasmtime_wasi_v29::{ResourceTable, WasiCtx, WasiCtxBuilder, WasiView};
+// preview2 lives at the root of wasmtime-wasi before v33
+use wasmtime_wasi_v29 as wasip2;
+
+crate::version::impl_version! {
+    runtime: V29,
+    name: "v29",
+    version: "wasmtime-29.0",
+    wasmtime: wasmtime_v29,
+    wasi: wasmtime_wasi_v29,
+    // wasmtime is built without its `winch` feature for this version
+    supports_winch: false,
+}
+
+impl WasiView for WasiP2State {
+    fn ctx(&mut self) -> &mut WasiCtx {
+        &mut self.ctx
+    }
+
+    fn table(&mut self) -> &mut ResourceTable {
+        &mut self.table
+    }
+}
+
+/// Look up the function `func_name` exported by the instance `interface`
+fn export_func<T>(
+    instance: &component::Instance,
+    store: &mut Store<T>,
+    interface: &str,
+    func_name: &str,
+) -> Result<component::Func> {
+    let ii = instance
+        .get_export(&mut *store, None, interface)
+        .ok_or_else(|| anyhow::anyhow!("miss interface: {interface}"))?;
+
+    let fi = instance
+        .get_export(&mut *store, Some(&ii), func_name)
+        .with_context(|| format!("miss export-index for func '{func_name}'"))?;
+
+    instance
+        .get_func(&mut *store, fi)
+        .with_context(|| format!("miss func '{func_name}'"))
+}
+
+/// Number of results of `func`
+fn results_len(func: &component::Func, store: &Store<WasiP2State>) -> usize {
+    func.results(store).len()
+}
+
+/// Version-neutral parameter types of `func`
+fn param_types(func: &component::Func, store: &Store<WasiP2State>) -> Vec<ValueType> {
+    func.params(store)
+        .iter()
+        .map(|(_, ty)| ValueType::from(ty.clone()))
+        .collect()
+}
+
+/// Version-neutral result types of `func`
+fn result_types(func: &component::Func, store: &Store<WasiP2State>) -> Vec<ValueType> {
+    func.results(store)
+        .iter()
+        .cloned()
+        .map(ValueType::from)
+        .collect()
+}
+
+/// Linear memory limit of the pooling allocator
+fn max_memory_size(config: &mut PoolingAllocationConfig, bytes: usize) {
+    config.max_memory_size(bytes);
+}
+
+/// Table size limit
+fn table_elements(limit: usize) -> Result<usize> {
+    Ok(limit)
+}
+
+crate::value::impl_from_type!(component::Type);
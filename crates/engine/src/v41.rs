@@ -1,57 +1,20 @@
-use crate::EngineError;
-use anyhow::Context;
+//! Engine on wasmtime v41, the code shared with the other versions being
+//! expanded by `crate::version::impl_version!`
 
-// Re-export wasmtime v41 types for convenience
-pub use wasmtime_v41::*;
+use wasmtime_wasi_v41::p1::{self as wasip1, WasiP1Ctx};
+use wasmtime_wasi_v41::p2::{self as wasip2, pipe::MemoryOutputPipe};
+use wasmtime_wasi_v41::{ResourceTable, WasiCtx, WasiCtxBuilder, WasiCtxView, WasiView};
 
-use wasmtime_wasi_v41::{ResourceTable, WasiCtx, WasiCtxView, WasiView};
-// Re-export wasmtime-wasi v41 as wasi for convenience
-// Note: We re-export the module contents to allow easier access
-pub use wasmtime_wasi_v41 as wasi;
+pub mod bindings;
 
-/// Execute a function from the loaded WASM module
-///
-/// # Arguments
-/// * `engine` - The engine instance
-/// * `module` - The loaded WASM module
-/// * `function_name` - The name of the function to execute
-/// * `_args` - Arguments to pass to the function (currently unused)
-///
-/// # Returns
-/// The result of the function execution
-pub fn execute(
-    engine: &Engine,
-    module: &Module,
-    function_name: &str,
-    _args: &[u8],
-) -> Result<Vec<u8>, EngineError> {
-    use wasmtime_v41::{Instance, Store};
-    let mut store = Store::new(engine, ());
-    let instance = Instance::new(&mut store, module, &[]).context("无法实例化模块")?;
-
-    let func = instance
-        .get_typed_func::<(), i32>(&mut store, function_name)
-        .with_context(|| format!("无法找到 '{}' 函数或签名不匹配", function_name))?;
-
-    let result = func
-        .call(&mut store, ())
-        .with_context(|| format!("函数 '{}' 调用失败", function_name))?;
-
-    Ok(result.to_le_bytes().to_vec())
-}
-
-/// Get the version information of the engine
-///
-/// # Returns
-/// The version string "wasmtime-41.0"
-pub fn version() -> &'static str {
-    "wasmtime-41.0"
-}
-
-#[derive(Default)]
-pub struct WasiP2State {
-    ctx: WasiCtx,
-    table: ResourceTable,
+crate::version::impl_version! {
+    runtime: V41,
+    name: "v41",
+    version: "wasmtime-41.0",
+    wasmtime: wasmtime_v41,
+    wasi: wasmtime_wasi_v41,
+    // enabled through the `winch` feature of wasmtime
+    supports_winch: true,
 }
 
 impl WasiView for WasiP2State {
@@ -63,29 +26,13 @@ impl WasiView for WasiP2State {
     }
 }
 
-/// Find a function in a component instance by name (supports "interface#function" format)
-///
-/// # Arguments
-/// * `instance` - The component instance
-/// * `store` - The store context
-/// * `name` - The function name in format "interface#function"
-///
-/// # Returns
-/// The function if found, otherwise an error
-///
-/// # Example
-/// ```ignore
-/// let func = find_func(&instance, &mut store, "sammyne:argon2/api#hash")?;
-/// ```
-pub fn find_func<T>(
+/// Look up the function `func_name` exported by the instance `interface`
+fn export_func<T>(
     instance: &component::Instance,
     store: &mut Store<T>,
-    name: &str,
+    interface: &str,
+    func_name: &str,
 ) -> Result<component::Func> {
-    let (interface, func_name) = name
-        .split_once('#')
-        .ok_or_else(|| anyhow::anyhow!("must in form of 'interface#func'"))?;
-
     let ii = instance
         .get_export_index(&mut *store, None, interface)
         .ok_or_else(|| anyhow::anyhow!("miss interface: {interface}"))?;
@@ -98,3 +45,38 @@ pub fn find_func<T>(
         .get_func(&mut *store, fi)
         .with_context(|| format!("miss func '{func_name}'"))
 }
+
+/// Number of results of `func`
+fn results_len(func: &component::Func, store: &Store<WasiP2State>) -> usize {
+    func.ty(store).results().len()
+}
+
+/// Version-neutral parameter types of `func`
+fn param_types(func: &component::Func, store: &Store<WasiP2State>) -> Vec<ValueType> {
+    func.ty(store)
+        .params()
+        .map(|(_, ty)| ValueType::from(ty))
+        .collect()
+}
+
+/// Version-neutral result types of `func`
+fn result_types(func: &component::Func, store: &Store<WasiP2State>) -> Vec<ValueType> {
+    func.ty(store).results().map(ValueType::from).collect()
+}
+
+/// Linear memory limit of the pooling allocator
+fn max_memory_size(config: &mut PoolingAllocationConfig, bytes: usize) {
+    config.max_memory_size(bytes);
+}
+
+/// Table size limit
+fn table_elements(limit: usize) -> Result<usize> {
+    Ok(limit)
+}
+
+crate::value::impl_from_type!(
+    component::Type,
+    component::Type::Future(f) => Self::Future(f.ty().map(|ty| Box::new(Self::from(ty)))),
+    component::Type::Stream(s) => Self::Stream(s.ty().map(|ty| Box::new(Self::from(ty)))),
+    component::Type::ErrorContext => Self::ErrorContext,
+);
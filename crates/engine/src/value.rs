@@ -0,0 +1,232 @@
+/// Version-neutral component model value
+///
+/// Mirrors `component::Val` of every wasmtime version except resource handles,
+/// which can't outlive the store they belong to. Each version module provides
+/// `From<Value>` for its own `component::Val`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    S8(i8),
+    U8(u8),
+    S16(i16),
+    U16(u16),
+    S32(i32),
+    U32(u32),
+    S64(i64),
+    U64(u64),
+    Float32(f32),
+    Float64(f64),
+    Char(char),
+    String(String),
+    List(Vec<Value>),
+    Record(Vec<(String, Value)>),
+    Tuple(Vec<Value>),
+    Variant(String, Option<Box<Value>>),
+    Enum(String),
+    Option(Option<Box<Value>>),
+    Result(Result<Option<Box<Value>>, Option<Box<Value>>>),
+    Flags(Vec<String>),
+}
+
+/// Version-neutral component model type
+///
+/// Mirrors `component::Type` of every wasmtime version. Resource handles and
+/// the async types of wasmtime v37 onwards have no [`Value`] but are kept so
+/// any signature can be described. Each version module provides
+/// `From<component::Type>` for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValueType {
+    Bool,
+    S8,
+    U8,
+    S16,
+    U16,
+    S32,
+    U32,
+    S64,
+    U64,
+    Float32,
+    Float64,
+    Char,
+    String,
+    List(Box<ValueType>),
+    Record(Vec<(String, ValueType)>),
+    Tuple(Vec<ValueType>),
+    Variant(Vec<(String, Option<ValueType>)>),
+    Enum(Vec<String>),
+    Option(Box<ValueType>),
+    Result {
+        ok: Option<Box<ValueType>>,
+        err: Option<Box<ValueType>>,
+    },
+    Flags(Vec<String>),
+    Own,
+    Borrow,
+    Future(Option<Box<ValueType>>),
+    Stream(Option<Box<ValueType>>),
+    ErrorContext,
+}
+
+impl Value {
+    /// Build a `list<u8>` value from raw bytes
+    pub fn bytes(v: impl IntoIterator<Item = u8>) -> Self {
+        Value::List(v.into_iter().map(Value::U8).collect())
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::String(s.to_owned())
+    }
+}
+
+/// Implement `From<Value>` for the `component::Val` of one wasmtime version
+///
+/// The variants of `component::Val` are identical across versions, so the
+/// conversion is written once and expanded inside each version module.
+macro_rules! impl_from_value {
+    ($val:ty) => {
+        impl From<$crate::Value> for $val {
+            fn from(v: $crate::Value) -> Self {
+                use $crate::Value;
+
+                let boxed = |v: Option<Box<Value>>| v.map(|v| Box::new(Self::from(*v)));
+
+                match v {
+                    Value::Bool(v) => Self::Bool(v),
+                    Value::S8(v) => Self::S8(v),
+                    Value::U8(v) => Self::U8(v),
+                    Value::S16(v) => Self::S16(v),
+                    Value::U16(v) => Self::U16(v),
+                    Value::S32(v) => Self::S32(v),
+                    Value::U32(v) => Self::U32(v),
+                    Value::S64(v) => Self::S64(v),
+                    Value::U64(v) => Self::U64(v),
+                    Value::Float32(v) => Self::Float32(v),
+                    Value::Float64(v) => Self::Float64(v),
+                    Value::Char(v) => Self::Char(v),
+                    Value::String(v) => Self::String(v),
+                    Value::List(v) => Self::List(v.into_iter().map(Self::from).collect()),
+                    Value::Record(v) => {
+                        Self::Record(v.into_iter().map(|(k, v)| (k, Self::from(v))).collect())
+                    }
+                    Value::Tuple(v) => Self::Tuple(v.into_iter().map(Self::from).collect()),
+                    Value::Variant(name, v) => Self::Variant(name, boxed(v)),
+                    Value::Enum(v) => Self::Enum(v),
+                    Value::Option(v) => Self::Option(boxed(v)),
+                    Value::Result(Ok(v)) => Self::Result(Ok(boxed(v))),
+                    Value::Result(Err(v)) => Self::Result(Err(boxed(v))),
+                    Value::Flags(v) => Self::Flags(v),
+                }
+            }
+        }
+    };
+}
+
+/// Implement `TryFrom<component::Val>` for [`Value`] for one wasmtime
+/// version, failing on resource handles and the async values
+macro_rules! impl_try_from_val {
+    ($val:ty) => {
+        impl TryFrom<$val> for $crate::Value {
+            type Error = $crate::EngineError;
+
+            fn try_from(v: $val) -> Result<Self, Self::Error> {
+                type Val = $val;
+
+                let boxed =
+                    |v: Option<Box<Val>>| v.map(|v| Self::try_from(*v).map(Box::new)).transpose();
+                let list = |v: Vec<Val>| -> Result<Vec<Self>, Self::Error> {
+                    v.into_iter().map(Self::try_from).collect()
+                };
+
+                let value = match v {
+                    Val::Bool(v) => Self::Bool(v),
+                    Val::S8(v) => Self::S8(v),
+                    Val::U8(v) => Self::U8(v),
+                    Val::S16(v) => Self::S16(v),
+                    Val::U16(v) => Self::U16(v),
+                    Val::S32(v) => Self::S32(v),
+                    Val::U32(v) => Self::U32(v),
+                    Val::S64(v) => Self::S64(v),
+                    Val::U64(v) => Self::U64(v),
+                    Val::Float32(v) => Self::Float32(v),
+                    Val::Float64(v) => Self::Float64(v),
+                    Val::Char(v) => Self::Char(v),
+                    Val::String(v) => Self::String(v),
+                    Val::List(v) => Self::List(list(v)?),
+                    Val::Record(v) => Self::Record(
+                        v.into_iter()
+                            .map(|(k, v)| Ok((k, Self::try_from(v)?)))
+                            .collect::<Result<_, Self::Error>>()?,
+                    ),
+                    Val::Tuple(v) => Self::Tuple(list(v)?),
+                    Val::Variant(name, v) => Self::Variant(name, boxed(v)?),
+                    Val::Enum(v) => Self::Enum(v),
+                    Val::Option(v) => Self::Option(boxed(v)?),
+                    Val::Result(Ok(v)) => Self::Result(Ok(boxed(v)?)),
+                    Val::Result(Err(v)) => Self::Result(Err(boxed(v)?)),
+                    Val::Flags(v) => Self::Flags(v),
+                    other => anyhow::bail!("{other:?} has no version-neutral value"),
+                };
+                Ok(value)
+            }
+        }
+    };
+}
+
+/// Implement `From<component::Type>` for [`ValueType`] for one wasmtime
+/// version
+///
+/// The extra arms map the variants only some versions have, e.g. the async
+/// types of v37 onwards.
+macro_rules! impl_from_type {
+    ($type:ty $(, $pat:pat => $expr:expr)* $(,)?) => {
+        impl From<$type> for $crate::ValueType {
+            fn from(ty: $type) -> Self {
+                type Type = $type;
+
+                let boxed = |ty: Option<Type>| ty.map(|ty| Box::new(Self::from(ty)));
+
+                match ty {
+                    Type::Bool => Self::Bool,
+                    Type::S8 => Self::S8,
+                    Type::U8 => Self::U8,
+                    Type::S16 => Self::S16,
+                    Type::U16 => Self::U16,
+                    Type::S32 => Self::S32,
+                    Type::U32 => Self::U32,
+                    Type::S64 => Self::S64,
+                    Type::U64 => Self::U64,
+                    Type::Float32 => Self::Float32,
+                    Type::Float64 => Self::Float64,
+                    Type::Char => Self::Char,
+                    Type::String => Self::String,
+                    Type::List(l) => Self::List(Box::new(Self::from(l.ty()))),
+                    Type::Record(r) => Self::Record(
+                        r.fields()
+                            .map(|f| (f.name.to_owned(), Self::from(f.ty)))
+                            .collect(),
+                    ),
+                    Type::Tuple(t) => Self::Tuple(t.types().map(Self::from).collect()),
+                    Type::Variant(v) => Self::Variant(
+                        v.cases()
+                            .map(|c| (c.name.to_owned(), c.ty.map(Self::from)))
+                            .collect(),
+                    ),
+                    Type::Enum(e) => Self::Enum(e.names().map(str::to_owned).collect()),
+                    Type::Option(o) => Self::Option(Box::new(Self::from(o.ty()))),
+                    Type::Result(r) => Self::Result {
+                        ok: boxed(r.ok()),
+                        err: boxed(r.err()),
+                    },
+                    Type::Flags(f) => Self::Flags(f.names().map(str::to_owned).collect()),
+                    Type::Own(_) => Self::Own,
+                    Type::Borrow(_) => Self::Borrow,
+                    $($pat => $expr,)*
+                }
+            }
+        }
+    };
+}
+
+pub(crate) use {impl_from_type, impl_from_value, impl_try_from_val};
@@ -0,0 +1,896 @@
+/// Expand the engine of one wasmtime version inside its module: the core
+/// module helpers, `WasiP2State`, `find_func` and the marker type
+/// implementing [`crate::Runtime`]
+///
+/// Most of the wasmtime and wasmtime-wasi API is identical across versions,
+/// so it's written once here. The module keeps by hand what moved or changed
+/// signature between versions, and the expansion refers to it by name:
+///
+/// * imports of `WasiP1Ctx`, `MemoryOutputPipe`, `ResourceTable`, `WasiCtx`
+///   and `WasiCtxBuilder`, plus the `wasip1` and `wasip2` modules holding the
+///   `add_to_linker_*` functions
+/// * the `WasiView` impl of `WasiP2State`
+/// * `fn export_func(instance, store, interface, func_name)`, looking up a
+///   function exported by an interface
+/// * `fn results_len`, `fn param_types` and `fn result_types` of a
+///   `component::Func`
+/// * `fn max_memory_size(config, bytes)` and `fn table_elements(limit)`,
+///   the linear memory and table limits, the latter failing when the limit
+///   doesn't fit the type of the version
+/// * `From<component::Type>` through [`crate::value::impl_from_type`]
+///
+/// # Example
+/// ```ignore
+/// crate::version::impl_version! {
+///     runtime: V41,
+///     name: "v41",
+///     version: "wasmtime-41.0",
+///     wasmtime: wasmtime_v41,
+///     wasi: wasmtime_wasi_v41,
+///     supports_winch: true,
+/// }
+/// ```
+macro_rules! impl_version {
+    (
+        runtime: $runtime:ident,
+        name: $name:literal,
+        version: $version:literal,
+        wasmtime: $wasmtime:ident,
+        wasi: $wasi:ident,
+        supports_winch: $supports_winch:literal $(,)?
+    ) => {
+        use anyhow::Context;
+        use std::future::Future;
+        use std::path::Path;
+        use std::time::Duration;
+        use $crate::epoch::{NO_DEADLINE, timeout_ticks};
+        use $crate::wasi_options::{CAPTURE_CAPACITY, random_bytes};
+        use $crate::{
+            Allocator, EngineError, EngineOptions, PoolingLimits, Profile, Stdio, ValueType,
+            WasiOptions,
+        };
+
+        // Re-export wasmtime types for convenience
+        pub use $wasmtime::*;
+        // Re-export wasmtime-wasi as wasi for convenience
+        pub use $wasi as wasi;
+
+        /// Execute a function from the loaded WASM module
+        ///
+        /// # Arguments
+        /// * `engine` - The engine instance
+        /// * `module` - The loaded WASM module
+        /// * `function_name` - The name of the function to execute
+        /// * `args` - Arguments to pass to the function, checked against its signature
+        ///
+        /// # Returns
+        /// All results of the function execution
+        pub fn execute(
+            engine: &Engine,
+            module: &Module,
+            function_name: &str,
+            args: &[Val],
+        ) -> Result<Vec<Val>, EngineError> {
+            let mut store = store_with(engine, ());
+            let instance = Instance::new(&mut store, module, &[]).context("无法实例化模块")?;
+
+            call_core(&instance, &mut store, function_name, args)
+        }
+
+        /// Data of the stores of core modules: the WASI preview1 context and
+        /// the contract host of erc20
+        pub struct CoreState {
+            wasi: WasiP1Ctx,
+            contract: $crate::contract::ContractHost,
+        }
+
+        /// Link WASI preview1 and the host functions imported by the
+        /// wasmi-benchmarks modules into a core linker
+        ///
+        /// * `bench.start` / `bench.end` - sightglass markers, no-ops here
+        /// * `env.clock_ms` - milliseconds elapsed since the linker was created
+        /// * `seal0.*` / `__unstable__.*` - the pallet-contracts imports of
+        ///   erc20, see [`crate::contract`]
+        ///
+        /// Any other import of `module` is defined to trap when called.
+        pub fn core_linker(engine: &Engine, module: &Module) -> Result<Linker<CoreState>> {
+            let mut linker = Linker::new(engine);
+            wasip1::add_to_linker_sync(&mut linker, |t: &mut CoreState| &mut t.wasi)
+                .context("link wasip1")?;
+
+            linker.func_wrap("bench", "start", || {})?;
+            linker.func_wrap("bench", "end", || {})?;
+
+            let epoch = std::time::Instant::now();
+            linker.func_wrap("env", "clock_ms", move || {
+                epoch.elapsed().as_millis() as i32
+            })?;
+
+            link_contract_host(&mut linker)?;
+            linker.define_unknown_imports_as_traps(module)?;
+
+            Ok(linker)
+        }
+
+        /// Link the pallet-contracts imports of erc20 to the
+        /// [`crate::contract::ContractHost`] of the store
+        fn link_contract_host(linker: &mut Linker<CoreState>) -> Result<()> {
+            use $crate::contract::{CALLER, ContractReturn, KEY_NOT_FOUND};
+
+            linker.func_wrap(
+                "seal0",
+                "seal_input",
+                |mut caller: Caller<'_, CoreState>, out: u32, out_len: u32| {
+                    let input = caller.data().contract.input.clone();
+                    write_output(&mut caller, out, out_len, &input)
+                },
+            )?;
+            linker.func_wrap(
+                "seal0",
+                "seal_caller",
+                |mut caller: Caller<'_, CoreState>, out: u32, out_len: u32| {
+                    write_output(&mut caller, out, out_len, &CALLER)
+                },
+            )?;
+            // Calls never carry value
+            linker.func_wrap(
+                "seal0",
+                "seal_value_transferred",
+                |mut caller: Caller<'_, CoreState>, out: u32, out_len: u32| {
+                    write_output(&mut caller, out, out_len, &0u128.to_le_bytes())
+                },
+            )?;
+            // Events are dropped, so are the hashes of their topics
+            linker.func_wrap(
+                "seal0",
+                "seal_deposit_event",
+                |_: u32, _: u32, _: u32, _: u32| {},
+            )?;
+            linker.func_wrap("seal0", "seal_hash_blake2_256", |_: u32, _: u32, _: u32| {})?;
+            linker.func_wrap(
+                "seal0",
+                "seal_return",
+                |mut caller: Caller<'_, CoreState>,
+                 flags: u32,
+                 data: u32,
+                 len: u32|
+                 -> Result<()> {
+                    let data = read_bytes(&mut caller, data, len)?;
+                    Err(ContractReturn { flags, data }.into())
+                },
+            )?;
+            linker.func_wrap(
+                "__unstable__",
+                "seal_get_storage",
+                |mut caller: Caller<'_, CoreState>,
+                 key: u32,
+                 key_len: u32,
+                 out: u32,
+                 out_len: u32|
+                 -> Result<u32> {
+                    let key = read_bytes(&mut caller, key, key_len)?;
+                    let Some(value) = caller.data().contract.get_storage(&key) else {
+                        return Ok(KEY_NOT_FOUND);
+                    };
+                    let value = value.to_vec();
+                    write_output(&mut caller, out, out_len, &value)?;
+                    Ok(0)
+                },
+            )?;
+            linker.func_wrap(
+                "__unstable__",
+                "seal_set_storage",
+                |mut caller: Caller<'_, CoreState>,
+                 key: u32,
+                 key_len: u32,
+                 value: u32,
+                 value_len: u32|
+                 -> Result<u32> {
+                    let key = read_bytes(&mut caller, key, key_len)?;
+                    let value = read_bytes(&mut caller, value, value_len)?;
+                    Ok(caller.data_mut().contract.set_storage(key, value))
+                },
+            )?;
+
+            Ok(())
+        }
+
+        /// The memory of a contract, exported by
+        /// [`crate::contract::export_memory`]
+        fn contract_memory(caller: &mut Caller<'_, CoreState>) -> Result<Memory> {
+            caller
+                .get_export("memory")
+                .and_then(Extern::into_memory)
+                .context("the contract doesn't export its memory")
+        }
+
+        fn read_bytes(caller: &mut Caller<'_, CoreState>, ptr: u32, len: u32) -> Result<Vec<u8>> {
+            let memory = contract_memory(caller)?;
+            let mut bytes = vec![0; len as usize];
+            memory.read(&*caller, ptr as usize, &mut bytes)?;
+            Ok(bytes)
+        }
+
+        /// Write `data` to the buffer at `out`, whose capacity is read from and
+        /// whose length is written to the `u32` at `out_len`
+        fn write_output(
+            caller: &mut Caller<'_, CoreState>,
+            out: u32,
+            out_len: u32,
+            data: &[u8],
+        ) -> Result<()> {
+            let memory = contract_memory(caller)?;
+            let mut capacity = [0; 4];
+            memory.read(&*caller, out_len as usize, &mut capacity)?;
+            anyhow::ensure!(
+                data.len() <= u32::from_le_bytes(capacity) as usize,
+                "output buffer too small"
+            );
+
+            memory.write(&mut *caller, out as usize, data)?;
+            memory.write(
+                &mut *caller,
+                out_len as usize,
+                &(data.len() as u32).to_le_bytes(),
+            )?;
+            Ok(())
+        }
+
+        /// Create a store for core modules linked by [`core_linker`]
+        ///
+        /// When `dir` is given it is preopened read-only as "." in the guest,
+        /// which is where the sightglass-style modules (bz2, pulldown-cmark)
+        /// read their `default.input*` files from.
+        pub fn new_core_store(engine: &Engine, dir: Option<&Path>) -> Result<Store<CoreState>> {
+            let mut builder = WasiCtxBuilder::new();
+            if let Some(dir) = dir {
+                builder
+                    .preopened_dir(dir, ".", wasi::DirPerms::READ, wasi::FilePerms::READ)
+                    .with_context(|| format!("preopen {}", dir.display()))?;
+            }
+
+            let state = CoreState {
+                wasi: builder.build_p1(),
+                contract: Default::default(),
+            };
+            Ok(store_with(engine, state))
+        }
+
+        /// Create a store holding `data`
+        ///
+        /// When the engine consumes fuel the store starts with as much as it
+        /// can hold, so calls run to completion and [`crate::Runtime::fuel`]
+        /// tells what they consumed. Likewise its epoch deadline is never
+        /// reached until [`crate::Runtime::set_timeout`].
+        fn store_with<T: 'static>(engine: &Engine, data: T) -> Store<T> {
+            let mut store = Store::new(engine, data);
+            // fails only when fuel isn't enabled in the engine
+            store.set_fuel(u64::MAX).ok();
+            store.set_epoch_deadline(NO_DEADLINE);
+            store
+        }
+
+        /// Attach [`crate::Timeout`] to the trap of an expired epoch deadline
+        fn timeout_context(error: EngineError) -> EngineError {
+            if error.downcast_ref::<Trap>() == Some(&Trap::Interrupt) {
+                error.context($crate::Timeout)
+            } else {
+                error
+            }
+        }
+
+        /// Call an export of a core instance with arguments checked against
+        /// its signature
+        ///
+        /// A WASI `proc_exit(0)` and a contract `seal_return` which doesn't
+        /// revert are treated as normal returns.
+        pub fn call_core<T>(
+            instance: &Instance,
+            store: &mut Store<T>,
+            function_name: &str,
+            args: &[Val],
+        ) -> Result<Vec<Val>, EngineError> {
+            let func = instance
+                .get_func(&mut *store, function_name)
+                .with_context(|| format!("无法找到 '{}' 函数", function_name))?;
+
+            let ty = func.ty(&*store);
+            check_args(store, &ty, args)
+                .with_context(|| format!("函数 '{}' 签名不匹配", function_name))?;
+
+            let mut results = vec![Val::I32(0); ty.results().len()];
+            match func.call(&mut *store, args, &mut results) {
+                Ok(()) => Ok(results),
+                Err(err)
+                    if matches!(err.downcast_ref::<wasi::I32Exit>(), Some(wasi::I32Exit(0))) =>
+                {
+                    Ok(results)
+                }
+                Err(err)
+                    if err
+                        .downcast_ref::<$crate::contract::ContractReturn>()
+                        .is_some_and(|ret| !ret.reverted()) =>
+                {
+                    Ok(results)
+                }
+                Err(err) => Err(err.context(format!("函数 '{}' 调用失败", function_name))),
+            }
+        }
+
+        /// Check that `args` match the parameter types of `ty`
+        fn check_args<T>(store: &Store<T>, ty: &FuncType, args: &[Val]) -> Result<(), EngineError> {
+            if ty.params().len() != args.len() {
+                anyhow::bail!("期望 {} 个参数，实际 {} 个", ty.params().len(), args.len());
+            }
+
+            for (i, (expect, arg)) in ty.params().zip(args).enumerate() {
+                if !arg.matches_ty(store, &expect)? {
+                    anyhow::bail!(
+                        "第 {} 个参数类型不匹配：期望 {}，实际 {}",
+                        i + 1,
+                        expect,
+                        arg.ty(store)?
+                    );
+                }
+            }
+
+            Ok(())
+        }
+
+        /// Precompile a component file into a `.cwasm` artifact
+        ///
+        /// # Arguments
+        /// * `engine` - The engine the artifact will be loaded with
+        /// * `src` - Path to the WASM component file
+        /// * `dst` - Path the artifact is written to
+        ///
+        /// # Returns
+        /// The size of the artifact in bytes
+        pub fn precompile_component_file(engine: &Engine, src: &Path, dst: &Path) -> Result<usize> {
+            let bytes = std::fs::read(src).with_context(|| format!("read {}", src.display()))?;
+            let artifact = engine.precompile_component(&bytes).with_context(|| {
+                format!(
+                    concat!("Failed to precompile ", $name, " component: {}"),
+                    src.display()
+                )
+            })?;
+            std::fs::write(dst, &artifact).with_context(|| format!("write {}", dst.display()))?;
+
+            Ok(artifact.len())
+        }
+
+        /// Load a precompiled `.cwasm` component artifact from file
+        ///
+        /// # Safety
+        /// The artifact must be produced by [`precompile_component_file`] or
+        /// `Component::serialize` of the same wasmtime version, and the file
+        /// must not be modified while the component is alive, see
+        /// `Component::deserialize_file`.
+        pub unsafe fn load_precompiled_component(
+            engine: &Engine,
+            path: &Path,
+        ) -> Result<component::Component> {
+            unsafe { component::Component::deserialize_file(engine, path) }.with_context(|| {
+                format!(
+                    concat!("Failed to load ", $name, " artifact from: {}"),
+                    path.display()
+                )
+            })
+        }
+
+        /// Apply version-neutral compiler settings to `config`
+        fn apply_profile(config: &mut Config, profile: &Profile) -> Result<(), EngineError> {
+            let strategy = match profile.strategy {
+                $crate::Strategy::Cranelift => Strategy::Cranelift,
+                $crate::Strategy::Winch if <$runtime as $crate::Runtime>::SUPPORTS_WINCH => {
+                    Strategy::Winch
+                }
+                $crate::Strategy::Winch => anyhow::bail!(concat!($name, " 不支持 Winch 编译器")),
+            };
+            let opt_level = match profile.opt_level {
+                $crate::OptLevel::None => OptLevel::None,
+                $crate::OptLevel::Speed => OptLevel::Speed,
+                $crate::OptLevel::SpeedAndSize => OptLevel::SpeedAndSize,
+            };
+
+            config.strategy(strategy);
+            config.cranelift_opt_level(opt_level);
+            config.wasm_simd(profile.simd);
+            // relaxed SIMD can't stay enabled without SIMD
+            config.wasm_relaxed_simd(profile.simd);
+
+            Ok(())
+        }
+
+        /// Translate version-neutral pooling limits into a
+        /// `PoolingAllocationConfig`
+        ///
+        /// # Returns
+        /// An error if a limit doesn't fit the type of this version
+        fn pooling_config(limits: &PoolingLimits) -> Result<PoolingAllocationConfig> {
+            let mut config = PoolingAllocationConfig::default();
+            config.total_component_instances(limits.total_component_instances);
+            config.total_core_instances(limits.total_core_instances);
+            config.total_memories(limits.total_memories);
+            config.total_tables(limits.total_tables);
+            config.max_core_instances_per_component(limits.max_core_instances_per_component);
+            config.max_memories_per_component(limits.max_memories_per_component);
+            config.max_tables_per_component(limits.max_tables_per_component);
+            max_memory_size(&mut config, limits.max_memory_size);
+            config.table_elements(table_elements(limits.table_elements)?);
+
+            Ok(config)
+        }
+
+        /// Get the version information of the engine
+        ///
+        /// # Returns
+        #[doc = concat!("The version string \"", $version, "\"")]
+        pub fn version() -> &'static str {
+            $version
+        }
+
+        /// Host state of component stores: a WASI context, its resource
+        /// table, the pipes capturing the output of the guest and the limits
+        /// of the store, if any
+        pub struct WasiP2State {
+            ctx: WasiCtx,
+            table: ResourceTable,
+            stdout: Option<MemoryOutputPipe>,
+            stderr: Option<MemoryOutputPipe>,
+            limits: Option<StoreLimits>,
+        }
+
+        impl Default for WasiP2State {
+            fn default() -> Self {
+                Self {
+                    ctx: WasiCtxBuilder::new().build(),
+                    table: ResourceTable::new(),
+                    stdout: None,
+                    stderr: None,
+                    limits: None,
+                }
+            }
+        }
+
+        impl WasiP2State {
+            /// Create the state with a WASI context built from `options`
+            ///
+            /// # Returns
+            /// An error if a preopened directory can't be opened or a limit
+            /// doesn't fit the type of this version
+            pub fn new(options: &WasiOptions) -> Result<Self> {
+                let mut builder = WasiCtxBuilder::new();
+                let mut stdout = None;
+                let mut stderr = None;
+                match options.stdio {
+                    Stdio::Null => {}
+                    Stdio::Inherit => {
+                        builder.inherit_stdio();
+                    }
+                    Stdio::Capture => {
+                        let pipes = (
+                            MemoryOutputPipe::new(CAPTURE_CAPACITY),
+                            MemoryOutputPipe::new(CAPTURE_CAPACITY),
+                        );
+                        builder.stdout(pipes.0.clone()).stderr(pipes.1.clone());
+                        stdout = Some(pipes.0);
+                        stderr = Some(pipes.1);
+                    }
+                }
+                builder.args(options.args.as_slice());
+                for (key, value) in &options.env {
+                    builder.env(key, value);
+                }
+                for preopen in &options.preopens {
+                    let (dir_perms, file_perms) = if preopen.read_only {
+                        (wasi::DirPerms::READ, wasi::FilePerms::READ)
+                    } else {
+                        (wasi::DirPerms::all(), wasi::FilePerms::all())
+                    };
+                    builder
+                        .preopened_dir(&preopen.host, &preopen.guest, dir_perms, file_perms)
+                        .with_context(|| format!("preopen {}", preopen.host.display()))?;
+                }
+                if let Some(now) = options.fixed_clock {
+                    builder.wall_clock(FixedClock(now));
+                    builder.monotonic_clock(FixedClock(now));
+                }
+                if let Some(seed) = options.random_seed {
+                    builder
+                        .secure_random(wasi::Deterministic::new(random_bytes(seed)))
+                        .insecure_random(wasi::Deterministic::new(random_bytes(!seed)))
+                        .insecure_random_seed(seed.into());
+                }
+
+                let limited = options.max_memory.is_some()
+                    || options.max_table_elements.is_some()
+                    || options.max_instances.is_some();
+                let limits = if limited {
+                    let mut limits = StoreLimitsBuilder::new().trap_on_grow_failure(true);
+                    if let Some(limit) = options.max_memory {
+                        limits = limits.memory_size(limit);
+                    }
+                    if let Some(limit) = options.max_table_elements {
+                        limits = limits.table_elements(table_elements(limit)?);
+                    }
+                    if let Some(limit) = options.max_instances {
+                        limits = limits.instances(limit);
+                    }
+                    Some(limits.build())
+                } else {
+                    None
+                };
+
+                Ok(Self {
+                    ctx: builder.build(),
+                    table: ResourceTable::new(),
+                    stdout,
+                    stderr,
+                    limits,
+                })
+            }
+
+            /// Bytes the guest has written to stdout so far, `None` unless the
+            /// state was created with [`Stdio::Capture`]
+            pub fn captured_stdout(&self) -> Option<Vec<u8>> {
+                self.stdout.as_ref().map(|pipe| pipe.contents().to_vec())
+            }
+
+            /// Bytes the guest has written to stderr so far, see
+            /// [`WasiP2State::captured_stdout`]
+            pub fn captured_stderr(&self) -> Option<Vec<u8>> {
+                self.stderr.as_ref().map(|pipe| pipe.contents().to_vec())
+            }
+        }
+
+        /// Clocks frozen at a point in time, see [`WasiOptions::fixed_clock`]
+        struct FixedClock(Duration);
+
+        impl wasi::HostWallClock for FixedClock {
+            fn resolution(&self) -> Duration {
+                Duration::from_nanos(1)
+            }
+
+            fn now(&self) -> Duration {
+                self.0
+            }
+        }
+
+        impl wasi::HostMonotonicClock for FixedClock {
+            fn resolution(&self) -> u64 {
+                1
+            }
+
+            fn now(&self) -> u64 {
+                self.0.as_nanos() as u64
+            }
+        }
+
+        /// Create a component store holding `state`, limited by the limits of
+        /// `state` if it has any
+        pub fn new_store(engine: &Engine, state: WasiP2State) -> Store<WasiP2State> {
+            let limited = state.limits.is_some();
+            let mut store = store_with(engine, state);
+            if limited {
+                store.limiter(|state| state.limits.as_mut().expect("limits of the state"));
+            }
+            store
+        }
+
+        /// Find a function in a component instance by name (supports
+        /// "interface#function" format)
+        ///
+        /// # Arguments
+        /// * `instance` - The component instance
+        /// * `store` - The store context
+        /// * `name` - The function name in format "interface#function"
+        ///
+        /// # Returns
+        /// The function if found, otherwise an error
+        ///
+        /// # Example
+        /// ```ignore
+        /// let func = find_func(&instance, &mut store, "sammyne:argon2/api#hash")?;
+        /// ```
+        pub fn find_func<T>(
+            instance: &component::Instance,
+            store: &mut Store<T>,
+            name: &str,
+        ) -> Result<component::Func> {
+            let (interface, func_name) = name
+                .split_once('#')
+                .ok_or_else(|| anyhow::anyhow!("must in form of 'interface#func'"))?;
+
+            export_func(instance, store, interface, func_name)
+        }
+
+        $crate::value::impl_from_value!(component::Val);
+        $crate::value::impl_try_from_val!(component::Val);
+
+        #[doc = concat!("Marker type implementing [`crate::Runtime`] with wasmtime ", $name)]
+        pub struct $runtime;
+
+        impl $crate::Runtime for $runtime {
+            const NAME: &'static str = $name;
+            const SUPPORTS_WINCH: bool = $supports_winch;
+
+            type Engine = Engine;
+            type Module = Module;
+            type Component = component::Component;
+            type Linker = component::Linker<WasiP2State>;
+            type InstancePre = component::InstancePre<WasiP2State>;
+            type Store = Store<WasiP2State>;
+            type Instance = component::Instance;
+            type Func = component::Func;
+            type CoreVal = Val;
+            type CoreInstancePre = InstancePre<CoreState>;
+            type CoreStore = Store<CoreState>;
+            type CoreInstance = Instance;
+            type Val = component::Val;
+
+            fn version() -> &'static str {
+                version()
+            }
+
+            fn new_engine(options: &EngineOptions) -> Result<Engine, EngineError> {
+                let mut config = Config::new();
+                config.wasm_component_model(true);
+                config.async_support(options.async_support);
+                config.parallel_compilation(options.parallel_compilation);
+                config.cranelift_nan_canonicalization(options.deterministic);
+                config.relaxed_simd_deterministic(options.deterministic);
+                config.consume_fuel(options.consume_fuel);
+                config.epoch_interruption(options.epoch_interruption);
+                apply_profile(&mut config, &options.profile)?;
+                if let Allocator::Pooling(limits) = &options.allocator {
+                    config.allocation_strategy(InstanceAllocationStrategy::Pooling(
+                        pooling_config(limits)?,
+                    ));
+                }
+
+                Engine::new(&config).context(concat!("Failed to create ", $name, " engine"))
+            }
+
+            fn load_module(engine: &Engine, bytes: &[u8]) -> Result<Module, EngineError> {
+                Module::from_binary(engine, bytes).context("加载 WASM 模块失败")
+            }
+
+            fn execute(
+                engine: &Engine,
+                module: &Module,
+                function_name: &str,
+                args: &[Val],
+            ) -> Result<Vec<Val>, EngineError> {
+                execute(engine, module, function_name, args)
+            }
+
+            fn core_instance_pre(
+                engine: &Engine,
+                module: &Module,
+            ) -> Result<InstancePre<CoreState>, EngineError> {
+                core_linker(engine, module)?
+                    .instantiate_pre(module)
+                    .context("instantiate-pre")
+            }
+
+            fn new_core_store(
+                engine: &Engine,
+                dir: Option<&Path>,
+            ) -> Result<Self::CoreStore, EngineError> {
+                new_core_store(engine, dir)
+            }
+
+            fn instantiate_core(
+                pre: &InstancePre<CoreState>,
+                store: &mut Self::CoreStore,
+            ) -> Result<Instance, EngineError> {
+                pre.instantiate(store)
+            }
+
+            fn call_core(
+                instance: &Instance,
+                store: &mut Self::CoreStore,
+                function_name: &str,
+                args: &[Val],
+            ) -> Result<Vec<Val>, EngineError> {
+                call_core(instance, store, function_name, args)
+            }
+
+            fn set_contract_input(store: &mut Self::CoreStore, input: Vec<u8>) {
+                store.data_mut().contract.input = input;
+            }
+
+            fn core_val_as_f64(val: &Val) -> Option<f64> {
+                match *val {
+                    Val::I32(v) => Some(v as f64),
+                    Val::I64(v) => Some(v as f64),
+                    Val::F32(bits) => Some(f32::from_bits(bits) as f64),
+                    Val::F64(bits) => Some(f64::from_bits(bits)),
+                    _ => None,
+                }
+            }
+
+            fn compile_component(
+                engine: &Engine,
+                bytes: &[u8],
+            ) -> Result<component::Component, EngineError> {
+                component::Component::new(engine, bytes).context(concat!(
+                    "Failed to compile ",
+                    $name,
+                    " component"
+                ))
+            }
+
+            fn load_component(
+                engine: &Engine,
+                path: &Path,
+            ) -> Result<component::Component, EngineError> {
+                component::Component::from_file(engine, path).with_context(|| {
+                    format!(
+                        concat!("Failed to load ", $name, " component from: {}"),
+                        path.display()
+                    )
+                })
+            }
+
+            fn precompile_component(engine: &Engine, bytes: &[u8]) -> Result<Vec<u8>, EngineError> {
+                engine.precompile_component(bytes).context(concat!(
+                    "Failed to precompile ",
+                    $name,
+                    " component"
+                ))
+            }
+
+            fn serialize_component(
+                component: &component::Component,
+            ) -> Result<Vec<u8>, EngineError> {
+                component.serialize()
+            }
+
+            unsafe fn deserialize_component(
+                engine: &Engine,
+                bytes: &[u8],
+            ) -> Result<component::Component, EngineError> {
+                unsafe { component::Component::deserialize(engine, bytes) }.context(concat!(
+                    "Failed to deserialize ",
+                    $name,
+                    " component"
+                ))
+            }
+
+            unsafe fn deserialize_component_file(
+                engine: &Engine,
+                path: &Path,
+            ) -> Result<component::Component, EngineError> {
+                unsafe { load_precompiled_component(engine, path) }
+            }
+
+            fn new_linker(
+                engine: &Engine,
+                options: &EngineOptions,
+            ) -> Result<component::Linker<WasiP2State>, EngineError> {
+                let mut linker = component::Linker::new(engine);
+                if options.async_support {
+                    wasip2::add_to_linker_async(&mut linker).context("link wasip2")?;
+                } else {
+                    wasip2::add_to_linker_sync(&mut linker).context("link wasip2")?;
+                }
+
+                Ok(linker)
+            }
+
+            fn link_instantiate_pre(
+                linker: &component::Linker<WasiP2State>,
+                component: &component::Component,
+            ) -> Result<Self::InstancePre, EngineError> {
+                linker.instantiate_pre(component).context("instantiate-pre")
+            }
+
+            fn new_store(engine: &Engine) -> Self::Store {
+                new_store(engine, WasiP2State::default())
+            }
+
+            fn new_store_with(
+                engine: &Engine,
+                options: &WasiOptions,
+            ) -> Result<Self::Store, EngineError> {
+                Ok(new_store(engine, WasiP2State::new(options)?))
+            }
+
+            fn captured_stdout(store: &Self::Store) -> Option<Vec<u8>> {
+                store.data().captured_stdout()
+            }
+
+            fn captured_stderr(store: &Self::Store) -> Option<Vec<u8>> {
+                store.data().captured_stderr()
+            }
+
+            fn fuel(store: &Self::Store) -> Option<u64> {
+                store.get_fuel().ok()
+            }
+
+            fn increment_epoch(engine: &Engine) {
+                engine.increment_epoch();
+            }
+
+            fn set_timeout(store: &mut Self::Store, timeout: Duration) {
+                store.set_epoch_deadline(timeout_ticks(timeout));
+            }
+
+            fn instantiate(
+                pre: &Self::InstancePre,
+                store: &mut Self::Store,
+            ) -> Result<component::Instance, EngineError> {
+                pre.instantiate(store).map_err(timeout_context)
+            }
+
+            fn instantiate_async(
+                pre: &Self::InstancePre,
+                store: &mut Self::Store,
+            ) -> impl Future<Output = Result<component::Instance, EngineError>> + Send {
+                let instantiate = pre.instantiate_async(store);
+                async move { instantiate.await.map_err(timeout_context) }
+            }
+
+            fn find_func(
+                instance: &component::Instance,
+                store: &mut Self::Store,
+                name: &str,
+            ) -> Result<component::Func, EngineError> {
+                find_func(instance, store, name)
+            }
+
+            fn results_len(func: &component::Func, store: &Self::Store) -> usize {
+                results_len(func, store)
+            }
+
+            fn param_types(func: &component::Func, store: &Self::Store) -> Vec<ValueType> {
+                param_types(func, store)
+            }
+
+            fn result_types(func: &component::Func, store: &Self::Store) -> Vec<ValueType> {
+                result_types(func, store)
+            }
+
+            fn call(
+                func: &component::Func,
+                store: &mut Self::Store,
+                params: &[component::Val],
+                results: &mut [component::Val],
+            ) -> Result<(), EngineError> {
+                func.call(store, params, results).map_err(timeout_context)
+            }
+
+            fn call_async(
+                func: &component::Func,
+                store: &mut Self::Store,
+                params: &[component::Val],
+                results: &mut [component::Val],
+            ) -> impl Future<Output = Result<(), EngineError>> + Send {
+                let call = func.call_async(store, params, results);
+                async move { call.await.map_err(timeout_context) }
+            }
+
+            fn post_return(
+                func: &component::Func,
+                store: &mut Self::Store,
+            ) -> Result<(), EngineError> {
+                func.post_return(store)
+            }
+
+            fn post_return_async(
+                func: &component::Func,
+                store: &mut Self::Store,
+            ) -> impl Future<Output = Result<(), EngineError>> + Send {
+                func.post_return_async(store)
+            }
+
+            fn is_trap(error: &EngineError) -> bool {
+                error.downcast_ref::<Trap>().is_some()
+            }
+        }
+    };
+}
+
+pub(crate) use impl_version;
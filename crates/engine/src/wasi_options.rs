@@ -0,0 +1,177 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Builder of the WASI context a `WasiP2State` is created with, shared by
+/// all wasmtime versions
+///
+/// The default matches `WasiP2State::default()`: no stdio, args, env or
+/// preopened directories, the clocks and random of the host, and no store
+/// limits beyond those of wasmtime.
+///
+/// # Example
+/// ```ignore
+/// let options = WasiOptions::new()
+///     .inherit_stdio()
+///     .arg("main.py")
+///     .env("RUST_LOG", "debug")
+///     .preopened_dir("testdata", "/data", true);
+/// let mut store = V41::new_store_with(&engine, &options)?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct WasiOptions {
+    pub(crate) stdio: Stdio,
+    pub(crate) args: Vec<String>,
+    pub(crate) env: Vec<(String, String)>,
+    pub(crate) preopens: Vec<Preopen>,
+    pub(crate) fixed_clock: Option<Duration>,
+    pub(crate) random_seed: Option<u64>,
+    pub(crate) max_memory: Option<usize>,
+    pub(crate) max_table_elements: Option<usize>,
+    pub(crate) max_instances: Option<usize>,
+}
+
+/// Where the stdin, stdout and stderr of the guest go
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Stdio {
+    /// Closed stdin, output discarded
+    #[default]
+    Null,
+    /// Those of the host process
+    Inherit,
+    /// Closed stdin, output kept in memory pipes of up to
+    /// [`CAPTURE_CAPACITY`] bytes each and read back by
+    /// `WasiP2State::captured_stdout`/`captured_stderr`
+    Capture,
+}
+
+/// Capacity of each pipe of [`Stdio::Capture`], writes beyond it fail in
+/// the guest
+pub const CAPTURE_CAPACITY: usize = 16 << 20;
+
+/// A host directory mapped into the guest
+#[derive(Debug, Clone)]
+pub(crate) struct Preopen {
+    pub(crate) host: PathBuf,
+    pub(crate) guest: String,
+    pub(crate) read_only: bool,
+}
+
+impl WasiOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set where the stdio of the guest goes
+    pub fn stdio(mut self, stdio: Stdio) -> Self {
+        self.stdio = stdio;
+        self
+    }
+
+    /// Inherit the stdio of the host process
+    pub fn inherit_stdio(self) -> Self {
+        self.stdio(Stdio::Inherit)
+    }
+
+    /// Capture stdout and stderr of the guest in memory
+    pub fn capture_output(self) -> Self {
+        self.stdio(Stdio::Capture)
+    }
+
+    /// Append an argument, the first one is the program name by convention
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Append arguments
+    pub fn args(mut self, args: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    /// Set an environment variable
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+
+    /// Preopen the host directory `host` as `guest`
+    ///
+    /// # Arguments
+    /// * `host` - The directory on the host, opened when the state is created
+    /// * `guest` - The path the guest sees it at, e.g. "." or "/data"
+    /// * `read_only` - Deny creating, writing and removing files in it
+    pub fn preopened_dir(
+        mut self,
+        host: impl Into<PathBuf>,
+        guest: impl Into<String>,
+        read_only: bool,
+    ) -> Self {
+        self.preopens.push(Preopen {
+            host: host.into(),
+            guest: guest.into(),
+            read_only,
+        });
+        self
+    }
+
+    /// Freeze `wasi:clocks`: the wall clock always reads `now` since the
+    /// UNIX epoch and the monotonic clock always reads `now` in nanoseconds
+    pub fn fixed_clock(mut self, now: Duration) -> Self {
+        self.fixed_clock = Some(now);
+        self
+    }
+
+    /// Seed both the secure and insecure generators of `wasi:random`
+    pub fn random_seed(mut self, seed: u64) -> Self {
+        self.random_seed = Some(seed);
+        self
+    }
+
+    /// Deterministic mode: clocks frozen at the UNIX epoch and `wasi:random`
+    /// seeded with 0
+    ///
+    /// Together with `EngineOptions::deterministic` a guest produces the
+    /// same output bit for bit on every run and wasmtime version.
+    pub fn deterministic(self) -> Self {
+        self.fixed_clock(Duration::ZERO).random_seed(0)
+    }
+
+    /// Limit each linear memory of the store to `bytes`
+    ///
+    /// Growing a memory beyond the limits of the store traps instead of
+    /// failing the `memory.grow` in the guest.
+    pub fn max_memory(mut self, bytes: usize) -> Self {
+        self.max_memory = Some(bytes);
+        self
+    }
+
+    /// Limit each table of the store to `elements`
+    pub fn max_table_elements(mut self, elements: usize) -> Self {
+        self.max_table_elements = Some(elements);
+        self
+    }
+
+    /// Limit the number of instances in the store, core instances included
+    pub fn max_instances(mut self, instances: usize) -> Self {
+        self.max_instances = Some(instances);
+        self
+    }
+}
+
+/// Bytes cycled by `wasi::Deterministic` for a seed of
+/// [`WasiOptions::random_seed`], generated by SplitMix64
+pub(crate) fn random_bytes(seed: u64) -> Vec<u8> {
+    const LEN: usize = 4096;
+
+    let mut state = seed;
+    let mut bytes = Vec::with_capacity(LEN);
+    while bytes.len() < LEN {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        bytes.extend_from_slice(&(z ^ (z >> 31)).to_le_bytes());
+    }
+    bytes
+}
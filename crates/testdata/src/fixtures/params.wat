@@ -0,0 +1,13 @@
+(module
+  ;; Sum of two i32 parameters
+  (func $add (param $a i32) (param $b i32) (result i32)
+    local.get $a
+    local.get $b
+    i32.add)
+  ;; Multi-value: returns the two parameters in reverse order
+  (func $swap (param $a i64) (param $b f64) (result f64 i64)
+    local.get $b
+    local.get $a)
+  (export "add" (func $add))
+  (export "swap" (func $swap))
+)
\ No newline at end of file
@@ -1,5 +1,7 @@
 use anyhow::{Result, bail};
 
+pub mod wasmi;
+
 /// Load a WASM fixture by name from the fixtures directory
 ///
 /// # Arguments
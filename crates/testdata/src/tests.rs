@@ -18,6 +18,15 @@ fn test_load_complex_fixture() {
     assert!(validate_wasm(&wasm_bytes).is_ok(), "Invalid WASM format");
 }
 
+#[test]
+fn test_load_params_fixture() {
+    let wasm_bytes = load_fixture("params");
+    assert!(!wasm_bytes.is_empty());
+
+    // Validate WASM format using anyhow error handling
+    assert!(validate_wasm(&wasm_bytes).is_ok(), "Invalid WASM format");
+}
+
 #[test]
 fn test_validate_wasm_empty() {
     let empty_bytes = vec![];
@@ -33,3 +42,13 @@ fn test_validate_wasm_invalid_magic() {
     assert!(result.is_err());
     assert_eq!(result.unwrap_err().to_string(), "无效的 WASM 魔术数字");
 }
+
+#[test]
+fn test_wasmi_modules_valid() {
+    for (name, wasm_bytes) in wasmi::ALL {
+        assert!(
+            validate_wasm(wasm_bytes).is_ok(),
+            "Invalid WASM format: {name}"
+        );
+    }
+}
@@ -1,24 +1,26 @@
 // Include wasm files from wasmi benchmarks
 // Source: https://github.com/wasmi-labs/wasmi-benchmarks
 // Commit: b9385cae9bfb8cf84dbb13996d0b948ca5826b53
+//
+// The files live in the wasmi-testdata crate. The large ones (ffmpeg.wasm and
+// spidermonkey.wasm) aren't tracked, run its `static/get.sh` to fetch them and
+// enable the `wasmi-large` feature.
 
-/// Argon2 password hashing algorithm
-pub const ARGON2: &[u8] = include_bytes!("../data/wasmi/argon2.wasm");
+pub use wasmi_testdata::{ARGON2, BZ2, COREMARK_MINIMAL, ERC20, PULLDOWN_CMARK};
+#[cfg(feature = "wasmi-large")]
+pub use wasmi_testdata::{FFMPEG, SPIDERMONKEY};
 
-/// bzip2 compression algorithm
-pub const BZ2: &[u8] = include_bytes!("../data/wasmi/bz2.wasm");
-
-/// CoreMark benchmark (minimal version)
-pub const COREMARK_MINIMAL: &[u8] = include_bytes!("../data/wasmi/coremark-minimal.wasm");
-
-/// ERC20 token standard contract
-pub const ERC20: &[u8] = include_bytes!("../data/wasmi/erc20.wasm");
-
-/// FFmpeg video processing library
-pub const FFMPEG: &[u8] = include_bytes!("../data/wasmi/ffmpeg.wasm");
-
-/// Markdown parser (CommonMark compliant)
-pub const PULLDOWN_CMARK: &[u8] = include_bytes!("../data/wasmi/pulldown-cmark.wasm");
-
-/// SpiderMonkey JavaScript engine
-pub const SPIDERMONKEY: &[u8] = include_bytes!("../data/wasmi/spidermonkey.wasm");
+/// Every module shipped by wasmi-benchmarks, paired with its file stem
+///
+/// ffmpeg and spidermonkey are only included with the `wasmi-large` feature.
+pub const ALL: &[(&str, &[u8])] = &[
+    ("argon2", ARGON2),
+    ("bz2", BZ2),
+    ("coremark-minimal", COREMARK_MINIMAL),
+    ("erc20", ERC20),
+    #[cfg(feature = "wasmi-large")]
+    ("ffmpeg", FFMPEG),
+    ("pulldown-cmark", PULLDOWN_CMARK),
+    #[cfg(feature = "wasmi-large")]
+    ("spidermonkey", SPIDERMONKEY),
+];
@@ -15,10 +15,12 @@ pub const COREMARK_MINIMAL: &[u8] = include_bytes!("../static/coremark-minimal.w
 pub const ERC20: &[u8] = include_bytes!("../static/erc20.wasm");
 
 /// FFmpeg video processing library
+#[cfg(feature = "large")]
 pub const FFMPEG: &[u8] = include_bytes!("../static/ffmpeg.wasm");
 
 /// Markdown parser (CommonMark compliant)
 pub const PULLDOWN_CMARK: &[u8] = include_bytes!("../static/pulldown-cmark.wasm");
 
 /// SpiderMonkey JavaScript engine
+#[cfg(feature = "large")]
 pub const SPIDERMONKEY: &[u8] = include_bytes!("../static/spidermonkey.wasm");